@@ -0,0 +1,4 @@
+//! Stand-in for the crates.io `lgbm-sys` so that `lgbm` uses the declarations
+//! and link directives of [`lightgbm_static_sys`].
+
+pub use lightgbm_static_sys::*;
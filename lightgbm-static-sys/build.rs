@@ -0,0 +1,423 @@
+//! Locates `lib_lightgbm.a` and the LightGBM headers and emits the link
+//! directives for a static build.
+//!
+//! Search order for the archive:
+//!
+//! 1. `LIGHTGBM_STATIC_LIB`: explicit path to `lib_lightgbm.a`
+//! 2. `LIGHTGBM_LIB_DIR`: directory containing `lib_lightgbm.a`
+//! 3. `/lightgbm-static/lib` and `/usr/local/lib` (the Docker image layouts)
+//!
+//! Headers are taken from `LIGHTGBM_INCLUDE_DIR`, or from `../include` next to
+//! the directory the archive was found in.
+//!
+//! With the `vendored` feature the archive is instead built from a local
+//! LightGBM checkout (`LIGHTGBM_SOURCE_DIR`, defaulting to `./LightGBM` in this
+//! crate) using the same cmake configuration as the Dockerfile's builder stage.
+//! No network access is needed, but the checkout must include its submodules.
+//!
+//! The OpenMP runtime is chosen with at most one of these features:
+//!
+//! - none of them: libgomp, linked dynamically
+//! - `openmp-gomp-static`: libgomp, linked statically (located via `$CC -print-file-name`)
+//! - `openmp-llvm`: LLVM libomp, which also provides the `GOMP_*` entry points GCC emits
+//! - `openmp-none`: no OpenMP at all; the archive must be built with `-DUSE_OPENMP=OFF`
+//!   (the vendored build does this automatically)
+//!
+//! `static-libstdcxx` additionally links libstdc++ statically. Together with
+//! `openmp-gomp-static` this leaves only glibc as a dynamic dependency.
+//!
+//! For musl targets (`*-unknown-linux-musl`) everything is linked statically:
+//! libstdc++ and the OpenMP runtime are taken from the musl C++ toolchain, so
+//! the archive itself must also be built against musl. Every environment
+//! variable above can be given per target, cc-rs style, e.g.
+//! `LIGHTGBM_LIB_DIR_aarch64_unknown_linux_musl`; when cross compiling the
+//! toolchain defaults to `<arch>-linux-musl-g++` (override with `CXX`/`CC`).
+//!
+//! The LightGBM version reported by `LIGHTGBM_VERSION` in the crate is taken from
+//! the `LIGHTGBM_VERSION` environment variable, or from a `VERSION.txt` placed
+//! next to the headers (the Dockerfile and the vendored build both put one there).
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+const LIB_FILE: &str = "lib_lightgbm.a";
+const HEADER_FILE: &str = "LightGBM/c_api.h";
+const VERSION_FILE: &str = "LightGBM/VERSION.txt";
+const DEFAULT_LIB_DIRS: &[&str] = &["/lightgbm-static/lib", "/usr/local/lib"];
+const DEFAULT_SOURCE_DIR: &str = "LightGBM";
+const SOURCE_SUBMODULES: &[&str] = &[
+    "external_libs/eigen",
+    "external_libs/fmt",
+    "external_libs/fast_double_parser",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OpenMp {
+    Gomp,
+    GompStatic,
+    Llvm,
+    LlvmStatic,
+    None,
+}
+
+impl OpenMp {
+    fn from_features() -> Self {
+        let selected = [
+            ("openmp-gomp-static", OpenMp::GompStatic),
+            ("openmp-llvm", OpenMp::Llvm),
+            ("openmp-none", OpenMp::None),
+        ]
+        .into_iter()
+        .filter(|(feature, _)| has_feature(feature))
+        .collect::<Vec<_>>();
+        let openmp = match selected[..] {
+            [] => OpenMp::Gomp,
+            [(_, openmp)] => openmp,
+            _ => fail(&format!(
+                "only one OpenMP runtime may be selected, but got: {}",
+                selected
+                    .iter()
+                    .map(|(f, _)| *f)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        };
+        // musl binaries are fully static, so the runtime has to be as well.
+        match openmp {
+            OpenMp::Gomp if is_musl() => OpenMp::GompStatic,
+            OpenMp::Llvm if is_musl() => OpenMp::LlvmStatic,
+            openmp => openmp,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            OpenMp::Gomp => "gomp",
+            OpenMp::GompStatic => "gomp-static",
+            OpenMp::Llvm => "llvm",
+            OpenMp::LlvmStatic => "llvm-static",
+            OpenMp::None => "none",
+        }
+    }
+}
+
+/// Flags from the `cmake` invocation in the Dockerfile's builder stage.
+fn cmake_defines(openmp: OpenMp) -> Vec<(&'static str, String)> {
+    let flags = if openmp == OpenMp::None {
+        "-static-libgcc -static-libstdc++ -pthread"
+    } else {
+        "-static-libgcc -static-libstdc++ -fopenmp -pthread"
+    };
+    let use_openmp = if openmp == OpenMp::None { "OFF" } else { "ON" };
+    let mut defines = vec![("CMAKE_C_COMPILER", cc()), ("CMAKE_CXX_COMPILER", cxx())];
+    if is_cross() {
+        defines.push(("CMAKE_SYSTEM_NAME", "Linux".to_string()));
+        defines.push(("CMAKE_SYSTEM_PROCESSOR", target_arch()));
+    }
+    let common = [
+        ("CMAKE_BUILD_TYPE", "Release"),
+        ("USE_OPENMP", use_openmp),
+        ("USE_GPU", "OFF"),
+        ("USE_SWIG", "OFF"),
+        ("USE_HDFS", "OFF"),
+        ("USE_R35", "OFF"),
+        ("USE_TIMETAG", "OFF"),
+        ("BUILD_STATIC_LIB", "ON"),
+        ("BUILD_CLI", "OFF"),
+        ("CMAKE_CXX_FLAGS", flags),
+        ("CMAKE_EXE_LINKER_FLAGS", flags),
+        ("CMAKE_POSITION_INDEPENDENT_CODE", "ON"),
+    ];
+    defines.extend(
+        common
+            .into_iter()
+            .map(|(key, value)| (key, value.to_string())),
+    );
+    defines
+}
+
+fn main() {
+    let openmp = OpenMp::from_features();
+    println!("cargo::rustc-env=LIGHTGBM_OPENMP_RUNTIME={}", openmp.name());
+    if env::var("DOCS_RS").is_ok() {
+        return;
+    }
+
+    let (lib, include_dir) = if has_feature("vendored") {
+        build_vendored(openmp)
+    } else {
+        let lib = find_lib();
+        let include_dir = find_include_dir(lib.parent().unwrap());
+        (lib, include_dir)
+    };
+    let lib_dir = lib.parent().unwrap();
+    rerun_if_changed(&lib);
+    if openmp == OpenMp::None && archive_uses_openmp(&lib) {
+        fail(&format!(
+            "`openmp-none` is enabled, but `{}` references OpenMP symbols.\n\
+             Rebuild LightGBM with -DUSE_OPENMP=OFF or enable `vendored`.",
+            lib.display()
+        ));
+    }
+
+    println!("cargo::rustc-link-search=native={}", lib_dir.display());
+    println!("cargo::rustc-link-lib=static=_lightgbm");
+    if has_feature("static-libstdcxx") || is_musl() {
+        link_static_from_compiler("stdc++");
+    } else {
+        println!("cargo::rustc-link-lib=dylib=stdc++");
+    }
+    match openmp {
+        OpenMp::Gomp => println!("cargo::rustc-link-lib=dylib=gomp"),
+        OpenMp::GompStatic => link_static_from_compiler("gomp"),
+        OpenMp::Llvm => println!("cargo::rustc-link-lib=dylib=omp"),
+        OpenMp::LlvmStatic => link_static_from_compiler("omp"),
+        OpenMp::None => {}
+    }
+
+    if let Some(version) = find_version(&include_dir) {
+        println!("cargo::rustc-env=LIGHTGBM_VERSION={version}");
+    }
+
+    println!("cargo::metadata=lib_dir={}", lib_dir.display());
+    println!("cargo::metadata=include={}", include_dir.display());
+}
+
+fn find_lib() -> PathBuf {
+    if let Some(path) = try_env_var("LIGHTGBM_STATIC_LIB") {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            fail(&format!(
+                "LIGHTGBM_STATIC_LIB is set to `{}`, but that file does not exist",
+                path.display()
+            ));
+        }
+        return path;
+    }
+    let mut searched = Vec::new();
+    let dirs = try_env_var("LIGHTGBM_LIB_DIR")
+        .into_iter()
+        .chain(DEFAULT_LIB_DIRS.iter().map(|dir| dir.to_string()));
+    for dir in dirs {
+        let path = Path::new(&dir).join(LIB_FILE);
+        if path.is_file() {
+            return path;
+        }
+        searched.push(path);
+    }
+    fail(&format!(
+        "{LIB_FILE} not found. Searched:\n{}\n\
+         Set LIGHTGBM_STATIC_LIB to the archive or LIGHTGBM_LIB_DIR to its directory.",
+        to_list(&searched)
+    ));
+}
+
+fn find_include_dir(lib_dir: &Path) -> PathBuf {
+    let dir = match try_env_var("LIGHTGBM_INCLUDE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => lib_dir.join("../include"),
+    };
+    let header = dir.join(HEADER_FILE);
+    if !header.is_file() {
+        fail(&format!(
+            "{HEADER_FILE} not found in `{}`.\n\
+             Set LIGHTGBM_INCLUDE_DIR to the directory containing `LightGBM/`.",
+            dir.display()
+        ));
+    }
+    dir
+}
+
+/// Links `lib{name}.a` from the C++ toolchain's own library directory.
+fn link_static_from_compiler(name: &str) {
+    let file = format!("lib{name}.a");
+    let output = Command::new(cxx())
+        .arg(format!("-print-file-name={file}"))
+        .output();
+    let path = match output {
+        Ok(output) if output.status.success() => {
+            PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())
+        }
+        _ => fail(&format!(
+            "failed to ask `{}` for the location of {file}",
+            cxx()
+        )),
+    };
+    // The compiler echoes the bare file name back when it cannot find it.
+    if !path.is_absolute() || !path.is_file() {
+        fail(&format!(
+            "{file} not found by `{} -print-file-name`. Install the static library \
+             or drop the feature that requests it.",
+            cxx()
+        ));
+    }
+    println!(
+        "cargo::rustc-link-search=native={}",
+        path.parent().unwrap().display()
+    );
+    println!("cargo::rustc-link-lib=static={name}");
+}
+
+fn archive_uses_openmp(lib: &Path) -> bool {
+    let Ok(data) = fs::read(lib) else {
+        return false;
+    };
+    [b"GOMP_".as_slice(), b"__kmpc_".as_slice()]
+        .iter()
+        .any(|symbol| data.windows(symbol.len()).any(|w| w == *symbol))
+}
+
+fn build_vendored(openmp: OpenMp) -> (PathBuf, PathBuf) {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let source_dir = match try_env_var("LIGHTGBM_SOURCE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => manifest_dir.join(DEFAULT_SOURCE_DIR),
+    };
+    if !source_dir.join("CMakeLists.txt").is_file() {
+        fail(&format!(
+            "no LightGBM checkout found at `{}`.\n\
+             Set LIGHTGBM_SOURCE_DIR to a clone of https://github.com/microsoft/LightGBM.",
+            source_dir.display()
+        ));
+    }
+    for submodule in SOURCE_SUBMODULES {
+        let dir = source_dir.join(submodule);
+        if !dir.is_dir() || dir.read_dir().map_or(true, |mut d| d.next().is_none()) {
+            fail(&format!(
+                "`{}` is empty.\n\
+                 Run `git submodule update --init --recursive` in the LightGBM checkout.",
+                dir.display()
+            ));
+        }
+    }
+    for path in ["CMakeLists.txt", "include", "src"] {
+        rerun_if_changed(&source_dir.join(path));
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let build_dir = out_dir.join("build");
+    let install_dir = out_dir.join("install");
+
+    let mut configure = Command::new(cmake());
+    configure
+        .arg("-S")
+        .arg(&source_dir)
+        .arg("-B")
+        .arg(&build_dir)
+        .arg(format!("-DCMAKE_INSTALL_PREFIX={}", install_dir.display()));
+    for (key, value) in cmake_defines(openmp) {
+        configure.arg(format!("-D{key}={value}"));
+    }
+    run(&mut configure);
+
+    let mut build = Command::new(cmake());
+    build.arg("--build").arg(&build_dir);
+    if let Ok(jobs) = env::var("NUM_JOBS") {
+        build.arg("--parallel").arg(jobs);
+    }
+    run(&mut build);
+    run(Command::new(cmake()).arg("--install").arg(&build_dir));
+
+    let lib = install_dir.join("lib").join(LIB_FILE);
+    if !lib.is_file() {
+        fail(&format!(
+            "cmake finished but `{}` was not produced",
+            lib.display()
+        ));
+    }
+    let include_dir = find_include_dir(&install_dir.join("lib"));
+    let version = source_dir.join("VERSION.txt");
+    if version.is_file()
+        && let Err(e) = fs::copy(&version, include_dir.join(VERSION_FILE))
+    {
+        fail(&format!("failed to copy `{}`: {e}", version.display()));
+    }
+    (lib, include_dir)
+}
+
+fn find_version(include_dir: &Path) -> Option<String> {
+    if let Some(version) = try_env_var("LIGHTGBM_VERSION") {
+        return Some(version);
+    }
+    let path = include_dir.join(VERSION_FILE);
+    rerun_if_changed(&path);
+    let version = fs::read_to_string(path).ok()?;
+    Some(version.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn cmake() -> String {
+    try_env_var("CMAKE").unwrap_or_else(|| "cmake".to_string())
+}
+
+fn cc() -> String {
+    try_env_var("CC").unwrap_or_else(|| default_compiler("cc", "gcc"))
+}
+
+fn cxx() -> String {
+    try_env_var("CXX").unwrap_or_else(|| default_compiler("c++", "g++"))
+}
+
+fn default_compiler(native: &str, musl_cross: &str) -> String {
+    if is_musl() && is_cross() {
+        format!("{}-linux-musl-{musl_cross}", target_arch())
+    } else {
+        native.to_string()
+    }
+}
+
+fn target() -> String {
+    env::var("TARGET").unwrap()
+}
+
+fn target_arch() -> String {
+    env::var("CARGO_CFG_TARGET_ARCH").unwrap()
+}
+
+fn is_musl() -> bool {
+    env::var("CARGO_CFG_TARGET_ENV").is_ok_and(|env| env == "musl")
+}
+
+fn is_cross() -> bool {
+    env::var("HOST").ok() != env::var("TARGET").ok()
+}
+
+fn has_feature(feature: &str) -> bool {
+    let key = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    env::var_os(key).is_some()
+}
+
+fn run(command: &mut Command) {
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => fail(&format!("`{command:?}` failed with {status}")),
+        Err(e) => fail(&format!("failed to run `{command:?}`: {e}")),
+    }
+}
+
+fn to_list(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("  - {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("lightgbm-static-sys: {message}");
+    process::exit(1);
+}
+
+/// Reads `{key}_{target}` (with `-` replaced by `_`), falling back to `{key}`.
+fn try_env_var(key: &str) -> Option<String> {
+    let target_key = format!("{key}_{}", target().replace('-', "_"));
+    println!("cargo::rerun-if-env-changed={target_key}");
+    println!("cargo::rerun-if-env-changed={key}");
+    env::var(&target_key).or_else(|_| env::var(key)).ok()
+}
+
+fn rerun_if_changed(path: &Path) {
+    println!("cargo::rerun-if-changed={}", path.display());
+}
@@ -0,0 +1,385 @@
+//! FFI declarations for the [LightGBM C API](https://lightgbm.readthedocs.io/en/latest/C-API.html),
+//! linked against a statically built `lib_lightgbm.a`.
+//!
+//! The build script locates the archive; see `build.rs` for the environment
+//! variables it honours. The declarations mirror `LightGBM/c_api.h` and keep the
+//! same names and integer types as the bindgen output of that header.
+
+#![allow(non_snake_case)]
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub const C_API_DTYPE_FLOAT32: u32 = 0;
+pub const C_API_DTYPE_FLOAT64: u32 = 1;
+pub const C_API_DTYPE_INT32: u32 = 2;
+pub const C_API_DTYPE_INT64: u32 = 3;
+
+pub const C_API_PREDICT_NORMAL: u32 = 0;
+pub const C_API_PREDICT_RAW_SCORE: u32 = 1;
+pub const C_API_PREDICT_LEAF_INDEX: u32 = 2;
+pub const C_API_PREDICT_CONTRIB: u32 = 3;
+
+pub const C_API_MATRIX_TYPE_CSR: u32 = 0;
+pub const C_API_MATRIX_TYPE_CSC: u32 = 1;
+
+pub const C_API_FEATURE_IMPORTANCE_SPLIT: u32 = 0;
+pub const C_API_FEATURE_IMPORTANCE_GAIN: u32 = 1;
+
+/// LightGBM version of the linked archive, if the build script could determine it.
+pub const LIGHTGBM_VERSION: Option<&str> = option_env!("LIGHTGBM_VERSION");
+
+/// Whether the archive was built from source by the `vendored` feature.
+pub const VENDORED: bool = cfg!(feature = "vendored");
+
+/// Whether an OpenMP runtime is linked (i.e. `openmp-none` is not enabled).
+pub const OPENMP: bool = !cfg!(feature = "openmp-none");
+
+/// OpenMP runtime linked by the build script: `gomp`, `gomp-static`, `llvm`,
+/// `llvm-static` or `none`.
+pub const OPENMP_RUNTIME: &str = env!("LIGHTGBM_OPENMP_RUNTIME");
+
+pub type DatasetHandle = *mut c_void;
+pub type BoosterHandle = *mut c_void;
+pub type FastConfigHandle = *mut c_void;
+
+unsafe extern "C" {
+    /// [LGBM_GetLastError](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_GetLastError)
+    pub fn LGBM_GetLastError() -> *const c_char;
+
+    /// [LGBM_GetMaxThreads](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_GetMaxThreads)
+    pub fn LGBM_GetMaxThreads(out: *mut c_int) -> c_int;
+
+    /// [LGBM_SetMaxThreads](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_SetMaxThreads)
+    pub fn LGBM_SetMaxThreads(num_threads: c_int) -> c_int;
+}
+
+#[cfg(not(feature = "openmp-none"))]
+unsafe extern "C" {
+    /// [omp_get_max_threads](https://www.openmp.org/spec-html/5.0/openmpsu112.html)
+    pub fn omp_get_max_threads() -> c_int;
+}
+
+/// Maximum number of threads of the linked OpenMP runtime, 1 without one.
+///
+/// Gated on this crate's `openmp-none` feature, so dependents need not
+/// repeat the feature to know whether [`omp_get_max_threads`] exists.
+#[cfg(not(feature = "openmp-none"))]
+pub fn openmp_max_threads() -> usize {
+    unsafe { omp_get_max_threads() }.max(1) as usize
+}
+
+/// Maximum number of threads of the linked OpenMP runtime, 1 without one.
+#[cfg(feature = "openmp-none")]
+pub fn openmp_max_threads() -> usize {
+    1
+}
+
+unsafe extern "C" {
+    /// [LGBM_DatasetCreateFromFile](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetCreateFromFile)
+    pub fn LGBM_DatasetCreateFromFile(
+        filename: *const c_char,
+        parameters: *const c_char,
+        reference: DatasetHandle,
+        out: *mut DatasetHandle,
+    ) -> c_int;
+
+    /// [LGBM_DatasetCreateFromMat](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetCreateFromMat)
+    pub fn LGBM_DatasetCreateFromMat(
+        data: *const c_void,
+        data_type: c_int,
+        nrow: i32,
+        ncol: i32,
+        is_row_major: c_int,
+        parameters: *const c_char,
+        reference: DatasetHandle,
+        out: *mut DatasetHandle,
+    ) -> c_int;
+
+    /// [LGBM_DatasetCreateFromMats](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetCreateFromMats)
+    pub fn LGBM_DatasetCreateFromMats(
+        nmat: i32,
+        data: *mut *const c_void,
+        data_type: c_int,
+        nrow: *mut i32,
+        ncol: i32,
+        is_row_major: *mut c_int,
+        parameters: *const c_char,
+        reference: DatasetHandle,
+        out: *mut DatasetHandle,
+    ) -> c_int;
+
+    /// [LGBM_DatasetGetSubset](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetGetSubset)
+    pub fn LGBM_DatasetGetSubset(
+        handle: DatasetHandle,
+        used_row_indices: *const i32,
+        num_used_row_indices: i32,
+        parameters: *const c_char,
+        out: *mut DatasetHandle,
+    ) -> c_int;
+
+    /// [LGBM_DatasetSetFeatureNames](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetSetFeatureNames)
+    pub fn LGBM_DatasetSetFeatureNames(
+        handle: DatasetHandle,
+        feature_names: *mut *const c_char,
+        num_feature_names: c_int,
+    ) -> c_int;
+
+    /// [LGBM_DatasetGetFeatureNames](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetGetFeatureNames)
+    pub fn LGBM_DatasetGetFeatureNames(
+        handle: DatasetHandle,
+        len: c_int,
+        num_feature_names: *mut c_int,
+        buffer_len: usize,
+        out_buffer_len: *mut usize,
+        feature_names: *mut *mut c_char,
+    ) -> c_int;
+
+    /// [LGBM_DatasetFree](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetFree)
+    pub fn LGBM_DatasetFree(handle: DatasetHandle) -> c_int;
+
+    /// [LGBM_DatasetDumpText](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetDumpText)
+    pub fn LGBM_DatasetDumpText(handle: DatasetHandle, filename: *const c_char) -> c_int;
+
+    /// [LGBM_DatasetSetField](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetSetField)
+    pub fn LGBM_DatasetSetField(
+        handle: DatasetHandle,
+        field_name: *const c_char,
+        field_data: *const c_void,
+        num_element: c_int,
+        type_: c_int,
+    ) -> c_int;
+
+    /// [LGBM_DatasetGetField](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetGetField)
+    pub fn LGBM_DatasetGetField(
+        handle: DatasetHandle,
+        field_name: *const c_char,
+        out_len: *mut c_int,
+        out_ptr: *mut *const c_void,
+        out_type: *mut c_int,
+    ) -> c_int;
+
+    /// [LGBM_DatasetGetNumData](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetGetNumData)
+    pub fn LGBM_DatasetGetNumData(handle: DatasetHandle, out: *mut c_int) -> c_int;
+
+    /// [LGBM_DatasetGetNumFeature](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_DatasetGetNumFeature)
+    pub fn LGBM_DatasetGetNumFeature(handle: DatasetHandle, out: *mut c_int) -> c_int;
+}
+
+unsafe extern "C" {
+    /// [LGBM_BoosterCreate](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterCreate)
+    pub fn LGBM_BoosterCreate(
+        train_data: DatasetHandle,
+        parameters: *const c_char,
+        out: *mut BoosterHandle,
+    ) -> c_int;
+
+    /// [LGBM_BoosterCreateFromModelfile](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterCreateFromModelfile)
+    pub fn LGBM_BoosterCreateFromModelfile(
+        filename: *const c_char,
+        out_num_iterations: *mut c_int,
+        out: *mut BoosterHandle,
+    ) -> c_int;
+
+    /// [LGBM_BoosterLoadModelFromString](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterLoadModelFromString)
+    pub fn LGBM_BoosterLoadModelFromString(
+        model_str: *const c_char,
+        out_num_iterations: *mut c_int,
+        out: *mut BoosterHandle,
+    ) -> c_int;
+
+    /// [LGBM_BoosterFree](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterFree)
+    pub fn LGBM_BoosterFree(handle: BoosterHandle) -> c_int;
+
+    /// [LGBM_BoosterMerge](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterMerge)
+    pub fn LGBM_BoosterMerge(handle: BoosterHandle, other_handle: BoosterHandle) -> c_int;
+
+    /// [LGBM_BoosterAddValidData](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterAddValidData)
+    pub fn LGBM_BoosterAddValidData(handle: BoosterHandle, valid_data: DatasetHandle) -> c_int;
+
+    /// [LGBM_BoosterResetParameter](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterResetParameter)
+    pub fn LGBM_BoosterResetParameter(handle: BoosterHandle, parameters: *const c_char) -> c_int;
+
+    /// [LGBM_BoosterGetNumClasses](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetNumClasses)
+    pub fn LGBM_BoosterGetNumClasses(handle: BoosterHandle, out_len: *mut c_int) -> c_int;
+
+    /// [LGBM_BoosterUpdateOneIter](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterUpdateOneIter)
+    pub fn LGBM_BoosterUpdateOneIter(handle: BoosterHandle, is_finished: *mut c_int) -> c_int;
+
+    /// [LGBM_BoosterUpdateOneIterCustom](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterUpdateOneIterCustom)
+    pub fn LGBM_BoosterUpdateOneIterCustom(
+        handle: BoosterHandle,
+        grad: *const f32,
+        hess: *const f32,
+        is_finished: *mut c_int,
+    ) -> c_int;
+
+    /// [LGBM_BoosterRollbackOneIter](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterRollbackOneIter)
+    pub fn LGBM_BoosterRollbackOneIter(handle: BoosterHandle) -> c_int;
+
+    /// [LGBM_BoosterGetCurrentIteration](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetCurrentIteration)
+    pub fn LGBM_BoosterGetCurrentIteration(
+        handle: BoosterHandle,
+        out_iteration: *mut c_int,
+    ) -> c_int;
+
+    /// [LGBM_BoosterNumModelPerIteration](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterNumModelPerIteration)
+    pub fn LGBM_BoosterNumModelPerIteration(
+        handle: BoosterHandle,
+        out_tree_per_iteration: *mut c_int,
+    ) -> c_int;
+
+    /// [LGBM_BoosterNumberOfTotalModel](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterNumberOfTotalModel)
+    pub fn LGBM_BoosterNumberOfTotalModel(handle: BoosterHandle, out_models: *mut c_int) -> c_int;
+
+    /// [LGBM_BoosterGetEvalCounts](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetEvalCounts)
+    pub fn LGBM_BoosterGetEvalCounts(handle: BoosterHandle, out_len: *mut c_int) -> c_int;
+
+    /// [LGBM_BoosterGetEvalNames](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetEvalNames)
+    pub fn LGBM_BoosterGetEvalNames(
+        handle: BoosterHandle,
+        len: c_int,
+        out_len: *mut c_int,
+        buffer_len: usize,
+        out_buffer_len: *mut usize,
+        out_strs: *mut *mut c_char,
+    ) -> c_int;
+
+    /// [LGBM_BoosterGetFeatureNames](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetFeatureNames)
+    pub fn LGBM_BoosterGetFeatureNames(
+        handle: BoosterHandle,
+        len: c_int,
+        out_len: *mut c_int,
+        buffer_len: usize,
+        out_buffer_len: *mut usize,
+        out_strs: *mut *mut c_char,
+    ) -> c_int;
+
+    /// [LGBM_BoosterGetNumFeature](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetNumFeature)
+    pub fn LGBM_BoosterGetNumFeature(handle: BoosterHandle, out_len: *mut c_int) -> c_int;
+
+    /// [LGBM_BoosterGetEval](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetEval)
+    pub fn LGBM_BoosterGetEval(
+        handle: BoosterHandle,
+        data_idx: c_int,
+        out_len: *mut c_int,
+        out_results: *mut f64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterGetNumPredict](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetNumPredict)
+    pub fn LGBM_BoosterGetNumPredict(
+        handle: BoosterHandle,
+        data_idx: c_int,
+        out_len: *mut i64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterGetPredict](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterGetPredict)
+    pub fn LGBM_BoosterGetPredict(
+        handle: BoosterHandle,
+        data_idx: c_int,
+        out_len: *mut i64,
+        out_result: *mut f64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterCalcNumPredict](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterCalcNumPredict)
+    pub fn LGBM_BoosterCalcNumPredict(
+        handle: BoosterHandle,
+        num_row: c_int,
+        predict_type: c_int,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        out_len: *mut i64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterPredictForMat](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterPredictForMat)
+    pub fn LGBM_BoosterPredictForMat(
+        handle: BoosterHandle,
+        data: *const c_void,
+        data_type: c_int,
+        nrow: i32,
+        ncol: i32,
+        is_row_major: c_int,
+        predict_type: c_int,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        parameter: *const c_char,
+        out_len: *mut i64,
+        out_result: *mut f64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterPredictForMatSingleRow](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterPredictForMatSingleRow)
+    pub fn LGBM_BoosterPredictForMatSingleRow(
+        handle: BoosterHandle,
+        data: *const c_void,
+        data_type: c_int,
+        ncol: c_int,
+        is_row_major: c_int,
+        predict_type: c_int,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        parameter: *const c_char,
+        out_len: *mut i64,
+        out_result: *mut f64,
+    ) -> c_int;
+
+    /// [LGBM_BoosterPredictForMatSingleRowFastInit](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterPredictForMatSingleRowFastInit)
+    pub fn LGBM_BoosterPredictForMatSingleRowFastInit(
+        handle: BoosterHandle,
+        predict_type: c_int,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        data_type: c_int,
+        ncol: i32,
+        parameter: *const c_char,
+        out_fastConfig: *mut FastConfigHandle,
+    ) -> c_int;
+
+    /// [LGBM_BoosterPredictForMatSingleRowFast](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterPredictForMatSingleRowFast)
+    pub fn LGBM_BoosterPredictForMatSingleRowFast(
+        fastConfig_handle: FastConfigHandle,
+        data: *const c_void,
+        out_len: *mut i64,
+        out_result: *mut f64,
+    ) -> c_int;
+
+    /// [LGBM_FastConfigFree](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_FastConfigFree)
+    pub fn LGBM_FastConfigFree(fastConfig: FastConfigHandle) -> c_int;
+
+    /// [LGBM_BoosterSaveModel](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterSaveModel)
+    pub fn LGBM_BoosterSaveModel(
+        handle: BoosterHandle,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        feature_importance_type: c_int,
+        filename: *const c_char,
+    ) -> c_int;
+
+    /// [LGBM_BoosterSaveModelToString](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterSaveModelToString)
+    pub fn LGBM_BoosterSaveModelToString(
+        handle: BoosterHandle,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        feature_importance_type: c_int,
+        buffer_len: i64,
+        out_len: *mut i64,
+        out_str: *mut c_char,
+    ) -> c_int;
+
+    /// [LGBM_BoosterDumpModel](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterDumpModel)
+    pub fn LGBM_BoosterDumpModel(
+        handle: BoosterHandle,
+        start_iteration: c_int,
+        num_iteration: c_int,
+        feature_importance_type: c_int,
+        buffer_len: i64,
+        out_len: *mut i64,
+        out_str: *mut c_char,
+    ) -> c_int;
+
+    /// [LGBM_BoosterFeatureImportance](https://lightgbm.readthedocs.io/en/latest/C-API.html#c.LGBM_BoosterFeatureImportance)
+    pub fn LGBM_BoosterFeatureImportance(
+        handle: BoosterHandle,
+        num_iteration: c_int,
+        importance_type: c_int,
+        out_results: *mut f64,
+    ) -> c_int;
+}
@@ -0,0 +1,109 @@
+//! Single-row latency of [`FastPredictor`] against the general
+//! `LGBM_BoosterPredictForMat` path, which sizes and allocates its output
+//! and re-parses parameters on every call.
+//!
+//! Run with `cargo bench -p lightgbm-static --bench predict`; the numbers
+//! are meant for comparing the two paths on the same aarch64 host.
+
+use lgbm::{Booster, Dataset, Field, Mat, MatBuf, Parameters, PredictType, mat::RowMajor};
+use lightgbm_static::FastPredictor;
+use std::{
+    hint::black_box,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const NUM_ROWS: usize = 10_000;
+const NUM_FEATURES: usize = 28;
+const NUM_ITERATIONS: usize = 100;
+const NUM_PREDICTIONS: usize = 100_000;
+
+fn main() {
+    let (features, labels) = synthetic_data();
+    let booster = train(&features, &labels);
+    let parameters = Parameters::new();
+    println!(
+        "{} rows, {NUM_FEATURES} features, {NUM_ITERATIONS} trees, {NUM_PREDICTIONS} predictions on {}",
+        NUM_ROWS,
+        std::env::consts::ARCH
+    );
+
+    let matrix = bench("predict_for_mat", &features, |row| {
+        booster
+            .predict_for_mat(
+                Mat::from_row(row),
+                PredictType::Normal,
+                0,
+                None,
+                &parameters,
+            )
+            .unwrap()
+            .values()[0]
+    });
+
+    let mut predictor =
+        FastPredictor::<f64>::new(&booster, PredictType::Normal, 0, None, &parameters).unwrap();
+    let fast = bench("FastPredictor", &features, |row| {
+        predictor.predict(row).unwrap()[0]
+    });
+
+    println!("speedup: {:.2}x", matrix.as_secs_f64() / fast.as_secs_f64());
+}
+
+/// Times `predict` over `NUM_PREDICTIONS` rows after a warm-up pass.
+fn bench(
+    name: &str,
+    features: &MatBuf<f64, RowMajor>,
+    mut predict: impl FnMut(&[f64]) -> f64,
+) -> Duration {
+    let rows = (0..NUM_PREDICTIONS).map(|i| features.row(i % NUM_ROWS));
+    for row in rows.clone().take(NUM_ROWS) {
+        black_box(predict(black_box(row)));
+    }
+    let start = Instant::now();
+    for row in rows {
+        black_box(predict(black_box(row)));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:>16}: {:>8.0} ns/row",
+        elapsed.as_nanos() as f64 / NUM_PREDICTIONS as f64
+    );
+    elapsed
+}
+
+fn train(features: &MatBuf<f64, RowMajor>, labels: &[f32]) -> Booster {
+    let mut p = Parameters::new();
+    p.push("objective", "regression");
+    p.push("num_leaves", 31);
+    p.push("verbosity", -1);
+    let mut train = Dataset::from_mat(features, None, &p).unwrap();
+    train.set_field(Field::LABEL, labels).unwrap();
+    let mut booster = Booster::new(Arc::new(train), &p).unwrap();
+    for _ in 0..NUM_ITERATIONS {
+        booster.update_one_iter().unwrap();
+    }
+    booster
+}
+
+/// Deterministic features in [0, 1) with a non-linear label.
+fn synthetic_data() -> (MatBuf<f64, RowMajor>, Vec<f32>) {
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let values = (0..NUM_ROWS * NUM_FEATURES)
+        .map(|_| next())
+        .collect::<Vec<_>>();
+    let labels = values
+        .chunks_exact(NUM_FEATURES)
+        .map(|row| (row[0] * 3.0 + (row[1] * 6.0).sin() + row[2] * row[3]) as f32)
+        .collect();
+    (
+        MatBuf::from_vec(values, NUM_ROWS, NUM_FEATURES, RowMajor),
+        labels,
+    )
+}
@@ -0,0 +1,132 @@
+use lightgbm_static_sys::{LIGHTGBM_VERSION, OPENMP, OPENMP_RUNTIME, VENDORED, openmp_max_threads};
+use serde::Serialize;
+use std::thread;
+
+/// How `lib_lightgbm.a` got into the binary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMode {
+    /// Prebuilt archive found by `lightgbm-static-sys` (e.g. from the Docker image).
+    Static,
+    /// Archive compiled from a local checkout by the `vendored` feature.
+    StaticVendored,
+}
+
+impl std::fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkMode::Static => write!(f, "static"),
+            LinkMode::StaticVendored => write!(f, "static (vendored)"),
+        }
+    }
+}
+
+/// Runtime capability report, the Rust counterpart of `printBuildInfo` in
+/// `testapp/main.cpp`.
+#[derive(Clone, Debug, Serialize)]
+pub struct BuildInfo {
+    /// Target architecture (`std::env::consts::ARCH`).
+    pub arch: &'static str,
+    /// C library of the target (`gnu`, `musl`, ...).
+    pub target_env: &'static str,
+    /// SIMD features supported by the running CPU.
+    pub target_features: Vec<&'static str>,
+    /// Threads reported by [`std::thread::available_parallelism`].
+    pub hardware_threads: usize,
+    /// Whether LightGBM runs with OpenMP.
+    pub openmp: bool,
+    /// OpenMP runtime selected at build time, see [`lightgbm_static_sys::OPENMP_RUNTIME`].
+    pub openmp_runtime: &'static str,
+    /// Threads OpenMP will use for a parallel region (1 without OpenMP).
+    pub openmp_threads: usize,
+    /// LightGBM version of the linked archive, if known at build time.
+    pub lightgbm_version: Option<&'static str>,
+    pub link_mode: LinkMode,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            arch: std::env::consts::ARCH,
+            target_env: TARGET_ENV,
+            target_features: target_features(),
+            hardware_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            openmp: OPENMP,
+            openmp_runtime: OPENMP_RUNTIME,
+            openmp_threads: openmp_max_threads(),
+            lightgbm_version: LIGHTGBM_VERSION,
+            link_mode: if VENDORED {
+                LinkMode::StaticVendored
+            } else {
+                LinkMode::Static
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "LightGBM Build Information")?;
+        writeln!(f, "==========================")?;
+        writeln!(f, "Hardware threads available: {}", self.hardware_threads)?;
+        if self.openmp {
+            writeln!(f, "OpenMP: ENABLED ({})", self.openmp_runtime)?;
+            writeln!(f, "OpenMP threads: {}", self.openmp_threads)?;
+        } else {
+            writeln!(f, "OpenMP: DISABLED")?;
+        }
+        writeln!(f, "Architecture: {} ({})", self.arch, self.target_env)?;
+        if self.target_features.is_empty() {
+            writeln!(f, "SIMD features: none detected")?;
+        } else {
+            writeln!(f, "SIMD features: {}", self.target_features.join(", "))?;
+        }
+        writeln!(
+            f,
+            "LightGBM version: {}",
+            self.lightgbm_version.unwrap_or("unknown")
+        )?;
+        write!(f, "Link mode: {}", self.link_mode)
+    }
+}
+
+const TARGET_ENV: &str = if cfg!(target_env = "musl") {
+    "musl"
+} else if cfg!(target_env = "gnu") {
+    "gnu"
+} else {
+    "unknown"
+};
+
+#[cfg(target_arch = "aarch64")]
+fn target_features() -> Vec<&'static str> {
+    use std::arch::is_aarch64_feature_detected;
+    [
+        ("neon", is_aarch64_feature_detected!("neon")),
+        ("sve", is_aarch64_feature_detected!("sve")),
+        ("sve2", is_aarch64_feature_detected!("sve2")),
+    ]
+    .into_iter()
+    .filter_map(|(name, detected)| detected.then_some(name))
+    .collect()
+}
+
+#[cfg(target_arch = "x86_64")]
+fn target_features() -> Vec<&'static str> {
+    use std::arch::is_x86_feature_detected;
+    [
+        ("sse4.2", is_x86_feature_detected!("sse4.2")),
+        ("avx", is_x86_feature_detected!("avx")),
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("fma", is_x86_feature_detected!("fma")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+    ]
+    .into_iter()
+    .filter_map(|(name, detected)| detected.then_some(name))
+    .collect()
+}
+
+#[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
+fn target_features() -> Vec<&'static str> {
+    Vec::new()
+}
@@ -0,0 +1,107 @@
+use crate::{EvalHistory, Evaluation, InitModel, ffi::RawBooster};
+use lgbm::{Parameters, Result};
+use std::cell::RefCell;
+
+/// What a [`TrainingCallback`] wants the [`Trainer`](crate::Trainer) to do.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Control {
+    #[default]
+    Continue,
+    Stop,
+}
+
+/// State handed to a [`TrainingCallback`].
+pub struct CallbackContext<'a> {
+    /// 1-based iteration about to run or just completed.
+    pub iteration: usize,
+    /// Evaluations of this iteration; empty before it runs.
+    pub evaluations: &'a [Evaluation],
+    pub history: &'a EvalHistory,
+    pub(crate) booster: &'a RawBooster,
+    pub(crate) init_model: Option<&'a InitModel>,
+    pub(crate) reset: RefCell<Vec<Parameters>>,
+}
+
+impl CallbackContext<'_> {
+    /// The model so far as LightGBM text, including the trees of the init
+    /// model when resuming.
+    pub fn model_to_string(&self) -> Result<String> {
+        let model = self.booster.save_model_to_string(None)?;
+        match self.init_model {
+            Some(init_model) => init_model.merge(&model),
+            None => Ok(model),
+        }
+    }
+
+    /// Changes booster parameters, e.g. `learning_rate`, once the current
+    /// hook returns; see [`Trainer::reset_parameters`](crate::Trainer::reset_parameters).
+    ///
+    /// Resets requested in `before_iteration` apply to this iteration, those
+    /// from `after_iteration` to the next.
+    pub fn reset_parameters(&self, parameters: &Parameters) {
+        self.reset.borrow_mut().push(parameters.clone());
+    }
+}
+
+/// Hook into [`Trainer::update`](crate::Trainer::update), e.g. for logging,
+/// checkpointing or custom stop conditions.
+///
+/// Errors abort training. Closures taking a [`CallbackContext`] act as
+/// `after_iteration` callbacks.
+///
+/// Callbacks can change booster parameters with
+/// [`CallbackContext::reset_parameters`], e.g. for learning-rate schedules
+/// such as [`LearningRateSchedule`].
+pub trait TrainingCallback {
+    fn before_iteration(&mut self, ctx: &CallbackContext<'_>) -> Result<Control> {
+        let _ = ctx;
+        Ok(Control::Continue)
+    }
+
+    fn after_iteration(&mut self, ctx: &CallbackContext<'_>) -> Result<Control> {
+        let _ = ctx;
+        Ok(Control::Continue)
+    }
+}
+
+impl<F: FnMut(&CallbackContext<'_>) -> Result<Control>> TrainingCallback for F {
+    fn after_iteration(&mut self, ctx: &CallbackContext<'_>) -> Result<Control> {
+        self(ctx)
+    }
+}
+
+/// Sets `learning_rate` before every iteration from a function of the
+/// 1-based iteration.
+///
+/// ```no_run
+/// # use lightgbm_static::{LearningRateSchedule, Trainer};
+/// # fn f(trainer: &mut Trainer) {
+/// // Decay by 1% per iteration, starting from 0.1.
+/// trainer.add_callback(LearningRateSchedule::new(|i| 0.1 * 0.99f64.powi(i as i32 - 1)));
+/// # }
+/// ```
+pub struct LearningRateSchedule<F> {
+    schedule: F,
+}
+
+impl<F: FnMut(usize) -> f64> LearningRateSchedule<F> {
+    pub fn new(schedule: F) -> Self {
+        Self { schedule }
+    }
+}
+
+impl<F: FnMut(usize) -> f64> TrainingCallback for LearningRateSchedule<F> {
+    fn before_iteration(&mut self, ctx: &CallbackContext<'_>) -> Result<Control> {
+        let learning_rate = (self.schedule)(ctx.iteration);
+        if !(learning_rate.is_finite() && learning_rate > 0.0) {
+            return Err(lgbm::Error::from_message(&format!(
+                "learning rate {learning_rate} for iteration {}",
+                ctx.iteration
+            )));
+        }
+        let mut parameters = Parameters::new();
+        parameters.push("learning_rate", learning_rate);
+        ctx.reset_parameters(&parameters);
+        Ok(Control::Continue)
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{CallbackContext, Control, Metadata, TrainingCallback, ffi::RawBooster};
+use lgbm::{AsMat, Booster, FeatureData, Parameters, PredictType, Result};
+use std::{
+    ffi::CString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Saved model that training continues from.
+///
+/// LightGBM boosts on top of an init model through the datasets' `init_score`,
+/// which [`Trainer::with_init_model`](crate::Trainer::with_init_model) sets
+/// from [`InitModel::init_score`]. Saved models contain the trees of both runs.
+pub struct InitModel {
+    booster: Booster,
+    model: String,
+    num_iterations: usize,
+}
+
+impl InitModel {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let model = fs::read_to_string(path).map_err(lgbm::Error::from_error)?;
+        Self::from_string(model)
+    }
+
+    pub fn from_string(model: String) -> Result<Self> {
+        let (booster, num_iterations) = Booster::from_string(&to_cstring(&model)?)?;
+        Ok(Self {
+            booster,
+            model,
+            num_iterations,
+        })
+    }
+
+    /// Iterations already in the model.
+    pub fn num_iterations(&self) -> usize {
+        self.num_iterations
+    }
+
+    pub fn booster(&self) -> &Booster {
+        &self.booster
+    }
+
+    /// Raw scores of the model in LightGBM's `init_score` layout: all rows of
+    /// the first class, then all rows of the next.
+    pub fn init_score<T: FeatureData>(&self, mat: impl AsMat<T>) -> Result<Vec<f64>> {
+        let prediction = self.booster.predict_for_mat(
+            mat,
+            PredictType::RawScore,
+            0,
+            None,
+            &Parameters::new(),
+        )?;
+        let (num_data, num_class) = (prediction.num_data(), prediction.num_class());
+        let values = prediction.values();
+        Ok((0..num_class)
+            .flat_map(|class| (0..num_data).map(move |row| values[row * num_class + class]))
+            .collect())
+    }
+
+    /// Sets the init scores of `metadata`, for the rows of `mat`, to the raw
+    /// scores of the model.
+    pub(crate) fn apply<T: FeatureData>(
+        &self,
+        metadata: &mut Metadata,
+        mat: impl AsMat<T>,
+    ) -> Result<()> {
+        let num_data = metadata.num_data();
+        let init_score = self.init_score(mat)?;
+        let num_rows = init_score.len() / self.booster.num_model_per_iteration()?;
+        if num_rows != num_data {
+            return Err(lgbm::Error::from_message(&format!(
+                "{num_rows} feature rows for {num_data} rows of the dataset"
+            )));
+        }
+        metadata.set_init_score(init_score)
+    }
+
+    /// The init model's trees followed by those of `continued`, a model
+    /// trained on top of it, merged by LightGBM (`LGBM_BoosterMerge`).
+    pub(crate) fn merge(&self, continued: &str) -> Result<String> {
+        if !continued.contains("\nTree=") {
+            return Ok(self.model.clone());
+        }
+        let mut merged = RawBooster::from_string(continued)?;
+        merged.merge(&RawBooster::from_string(&self.model)?)?;
+        merged.save_model_to_string(None)
+    }
+}
+
+fn to_cstring(s: &str) -> Result<CString> {
+    CString::new(s).map_err(lgbm::Error::from_error)
+}
+
+/// [`TrainingCallback`] writing the model to `<dir>/model-<iteration>.txt`
+/// every `every` iterations.
+///
+/// Files are written under a temporary name and renamed into place, so a
+/// killed run never leaves a truncated snapshot behind.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    dir: PathBuf,
+    every: usize,
+    keep_last: Option<usize>,
+}
+
+impl Checkpoint {
+    /// `every` is clamped to at least one iteration.
+    pub fn new(dir: impl Into<PathBuf>, every: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            every: every.max(1),
+            keep_last: None,
+        }
+    }
+
+    /// Deletes all but the newest `keep` snapshots after writing one.
+    pub fn keep_last(mut self, keep: usize) -> Self {
+        self.keep_last = Some(keep.max(1));
+        self
+    }
+
+    pub fn path(&self, iteration: usize) -> PathBuf {
+        self.dir.join(format!("model-{iteration:08}.txt"))
+    }
+
+    /// Snapshots in `dir` as `(iteration, path)`, oldest first.
+    pub fn list(dir: &Path) -> io::Result<Vec<(usize, PathBuf)>> {
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let iteration = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("model-")?.strip_suffix(".txt"))
+                .and_then(|n| n.parse().ok());
+            if let Some(iteration) = iteration {
+                snapshots.push((iteration, path));
+            }
+        }
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
+    /// Newest snapshot in `dir`, if any.
+    pub fn latest(dir: &Path) -> io::Result<Option<PathBuf>> {
+        Ok(Self::list(dir)?.pop().map(|(_, path)| path))
+    }
+
+    fn write(&self, iteration: usize, model: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(iteration);
+        let tmp = path.with_extension("txt.tmp");
+        {
+            let mut file = fs::File::create(&tmp)?;
+            io::Write::write_all(&mut file, model.as_bytes())?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, &path)?;
+
+        if let Some(keep) = self.keep_last {
+            let snapshots = Self::list(&self.dir)?;
+            for (_, old) in &snapshots[..snapshots.len().saturating_sub(keep)] {
+                fs::remove_file(old)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TrainingCallback for Checkpoint {
+    fn after_iteration(&mut self, ctx: &CallbackContext<'_>) -> Result<Control> {
+        if ctx.iteration.is_multiple_of(self.every) {
+            let model = ctx.model_to_string()?;
+            self.write(ctx.iteration, &model).map_err(|e| {
+                lgbm::Error::from_message(&format!(
+                    "failed to write checkpoint to `{}`: {e}",
+                    self.dir.display()
+                ))
+            })?;
+        }
+        Ok(Control::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty directory unique to `name` and this process.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "lightgbm-static-checkpoint-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn iterations(dir: &Path) -> Vec<usize> {
+        Checkpoint::list(dir)
+            .unwrap()
+            .into_iter()
+            .map(|(iteration, _)| iteration)
+            .collect()
+    }
+
+    #[test]
+    fn list_sorts_by_iteration_and_skips_other_files() {
+        let dir = temp_dir("list");
+        for name in [
+            "model-00000010.txt",
+            "model-00000002.txt",
+            "model-00000100.txt",
+            "model-00000003.txt.tmp",
+            "model-latest.txt",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(iterations(&dir), [2, 10, 100]);
+        assert_eq!(
+            Checkpoint::latest(&dir).unwrap(),
+            Some(dir.join("model-00000100.txt"))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn latest_of_empty_dir_is_none() {
+        let dir = temp_dir("empty");
+        assert_eq!(Checkpoint::latest(&dir).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_keeps_last_snapshots() {
+        let dir = temp_dir("keep-last");
+        let checkpoint = Checkpoint::new(&dir, 5).keep_last(2);
+        for iteration in [5, 10, 15] {
+            checkpoint
+                .write(iteration, &format!("model {iteration}"))
+                .unwrap();
+        }
+        assert_eq!(iterations(&dir), [10, 15]);
+        assert_eq!(fs::read_to_string(checkpoint.path(15)).unwrap(), "model 15");
+        // No temporary files are left behind.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_keeps_everything_by_default() {
+        let dir = temp_dir("keep-all");
+        let checkpoint = Checkpoint::new(dir.join("nested"), 0);
+        assert_eq!(checkpoint.every, 1);
+        for iteration in 1..=3 {
+            checkpoint.write(iteration, "").unwrap();
+        }
+        assert_eq!(iterations(&dir.join("nested")), [1, 2, 3]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keep_last_keeps_at_least_one() {
+        assert_eq!(Checkpoint::new("dir", 1).keep_last(0).keep_last, Some(1));
+    }
+}
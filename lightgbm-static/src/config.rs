@@ -0,0 +1,545 @@
+use crate::{
+    ParamError, Params,
+    params::{ParameterKind, parameter_kind},
+};
+use lgbm::Parameters;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Part of a [`Config`] a parameter belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    /// Passed when constructing datasets, e.g. `max_bin`.
+    Dataset,
+    /// Passed when constructing the booster, e.g. `num_leaves`.
+    Booster,
+    /// Passed when predicting, e.g. `pred_early_stop`.
+    Prediction,
+}
+
+impl Section {
+    pub fn name(self) -> &'static str {
+        match self {
+            Section::Dataset => "dataset",
+            Section::Booster => "booster",
+            Section::Prediction => "prediction",
+        }
+    }
+
+    /// Section a known parameter goes to by default: prediction parameters
+    /// to `prediction`, everything else to `booster`.
+    fn of(key: &str) -> Self {
+        match parameter_kind(key) {
+            Some(ParameterKind::Prediction) => Section::Prediction,
+            _ => Section::Booster,
+        }
+    }
+
+    /// Whether parameters of `kind` may be set in this section. Dataset
+    /// parameters are also accepted in `booster`, as in LightGBM config
+    /// files, since training passes the booster parameters to datasets too.
+    fn accepts(self, kind: ParameterKind) -> bool {
+        match self {
+            Section::Dataset => matches!(kind, ParameterKind::General | ParameterKind::Dataset),
+            Section::Booster => kind != ParameterKind::Prediction,
+            Section::Prediction => {
+                matches!(kind, ParameterKind::General | ParameterKind::Prediction)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Section {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dataset" => Ok(Section::Dataset),
+            "booster" => Ok(Section::Booster),
+            "prediction" => Ok(Section::Prediction),
+            _ => Err(ConfigError::UnknownSection(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Toml(toml::de::Error),
+    Yaml(serde_yaml::Error),
+    UnknownSection(String),
+    Param {
+        section: Section,
+        source: ParamError,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "failed to read `{}`: {source}", path.display())
+            }
+            ConfigError::Toml(e) => write!(f, "invalid TOML: {e}"),
+            ConfigError::Yaml(e) => write!(f, "invalid YAML: {e}"),
+            ConfigError::UnknownSection(name) => write!(
+                f,
+                "unknown section `{name}`, expected `dataset`, `booster` or `prediction`"
+            ),
+            ConfigError::Param { section, source } => write!(f, "[{section}] {source}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Toml(e) => Some(e),
+            ConfigError::Yaml(e) => Some(e),
+            ConfigError::UnknownSection(_) => None,
+            ConfigError::Param { source, .. } => Some(source),
+        }
+    }
+}
+
+/// LightGBM parameters grouped by where they are used.
+///
+/// Loaded from TOML or YAML with one table per section:
+///
+/// ```toml
+/// [dataset]
+/// max_bin = 63
+///
+/// [booster]
+/// objective = "regression"
+/// metric = ["l2", "l1"]
+/// num_leaves = 10
+///
+/// [prediction]
+/// pred_early_stop = false
+/// ```
+///
+/// Keys are LightGBM parameter names or aliases. Unknown sections and
+/// parameters are errors, as are parameters in a section that does not use
+/// them, e.g. `num_leaves` under `[prediction]`.
+///
+/// Overrides are layered on top with [`Config::set`]; `lgbm-tool` applies the
+/// config file first, then `LGBM_TOOL_PARAMS`, then `--param` flags.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub dataset: Params,
+    pub booster: Params,
+    pub prediction: Params,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    dataset: BTreeMap<String, Value>,
+    #[serde(default)]
+    booster: BTreeMap<String, Value>,
+    #[serde(default)]
+    prediction: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Float(v) => write!(f, "{v}"),
+            Value::String(v) => write!(f, "{v}"),
+            Value::List(values) => {
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Loads a `.toml`, `.yaml` or `.yml` file. Any other file is read as a
+    /// LightGBM config file (`key = value` lines) into the booster section.
+    pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&text),
+            Some("yaml" | "yml") => Self::from_yaml_str(&text),
+            _ => Self::from_lightgbm_str(&text),
+        }
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, ConfigError> {
+        Self::from_file(toml::from_str(s).map_err(ConfigError::Toml)?)
+    }
+
+    pub fn from_yaml_str(s: &str) -> Result<Self, ConfigError> {
+        // An empty document is `null`, not an empty mapping.
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        Self::from_file(serde_yaml::from_str(s).map_err(ConfigError::Yaml)?)
+    }
+
+    /// Reads LightGBM's own config format: prediction parameters into the
+    /// prediction section, everything else into the booster section.
+    pub fn from_lightgbm_str(s: &str) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        for line in s.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ConfigError::Param {
+                    section: Section::Booster,
+                    source: ParamError::new(line, "expected `key = value`"),
+                });
+            };
+            config.insert(Section::of(key.trim()), key, value, false)?;
+        }
+        Ok(config)
+    }
+
+    fn from_file(file: ConfigFile) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        for (section, values) in [
+            (Section::Dataset, file.dataset),
+            (Section::Booster, file.booster),
+            (Section::Prediction, file.prediction),
+        ] {
+            for (key, value) in values {
+                config.insert(section, &key, &value.to_string(), false)?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// Renders the config as TOML that [`Config::from_toml_str`] reads back.
+    pub fn to_toml_string(&self) -> String {
+        let mut file = toml::Table::new();
+        for section in [Section::Dataset, Section::Booster, Section::Prediction] {
+            let pairs = self.section(section).to_pairs();
+            if pairs.is_empty() {
+                continue;
+            }
+            let table = pairs
+                .into_iter()
+                .map(|(key, value)| (key, toml_value(value)))
+                .collect();
+            file.insert(section.name().to_string(), toml::Value::Table(table));
+        }
+        file.to_string()
+    }
+
+    pub fn section(&self, section: Section) -> &Params {
+        match section {
+            Section::Dataset => &self.dataset,
+            Section::Booster => &self.booster,
+            Section::Prediction => &self.prediction,
+        }
+    }
+
+    pub fn section_mut(&mut self, section: Section) -> &mut Params {
+        match section {
+            Section::Dataset => &mut self.dataset,
+            Section::Booster => &mut self.booster,
+            Section::Prediction => &mut self.prediction,
+        }
+    }
+
+    /// Applies `[section.]key=value`, replacing an earlier value.
+    ///
+    /// Without a section prefix the parameter replaces the dataset or
+    /// prediction value if one is set there. Otherwise prediction parameters
+    /// go to the prediction section and everything else to `default`.
+    pub fn set(&mut self, default: Section, param: &str) -> Result<(), ConfigError> {
+        let Some((key, value)) = param.split_once('=') else {
+            return Err(ConfigError::Param {
+                section: default,
+                source: ParamError::new(param, "expected `key=value`"),
+            });
+        };
+        let key = key.trim();
+        let (section, key) = match key.split_once('.') {
+            Some((section, key)) => (section.parse()?, key),
+            None => {
+                let section = [Section::Dataset, Section::Prediction]
+                    .into_iter()
+                    .find(|&s| self.section(s).contains(key))
+                    .unwrap_or(if parameter_kind(key) == Some(ParameterKind::Prediction) {
+                        Section::Prediction
+                    } else {
+                        default
+                    });
+                (section, key)
+            }
+        };
+        self.insert(section, key, value, true)
+    }
+
+    fn insert(
+        &mut self,
+        section: Section,
+        key: &str,
+        value: &str,
+        replace: bool,
+    ) -> Result<(), ConfigError> {
+        let key = key.trim();
+        let Some(kind) = parameter_kind(key) else {
+            return Err(ConfigError::Param {
+                section,
+                source: ParamError::new(key, "unknown parameter"),
+            });
+        };
+        if !section.accepts(kind) {
+            return Err(ConfigError::Param {
+                section,
+                source: ParamError::new(key, format!("belongs in `[{}]`", Section::of(key))),
+            });
+        }
+        let params = self.section_mut(section);
+        let result = if replace {
+            params.replace(key, value)
+        } else {
+            params.set(key, value)
+        };
+        result.map_err(|source| ConfigError::Param { section, source })
+    }
+
+    /// Dataset and booster parameters combined, as used for training.
+    ///
+    /// Like `testapp/main.cpp`, the same string is passed to both dataset and
+    /// booster construction; a parameter set in both sections is an error.
+    pub fn training_params(&self) -> Result<Params, ConfigError> {
+        let mut params = self.dataset.clone();
+        params
+            .extend(&self.booster)
+            .map_err(|source| ConfigError::Param {
+                section: Section::Booster,
+                source,
+            })?;
+        params.validate().map_err(|source| ConfigError::Param {
+            section: Section::Booster,
+            source,
+        })?;
+        Ok(params)
+    }
+
+    pub fn training_parameters(&self) -> Result<Parameters, ConfigError> {
+        self.training_params()?
+            .to_parameters()
+            .map_err(|source| ConfigError::Param {
+                section: Section::Booster,
+                source,
+            })
+    }
+
+    pub fn prediction_parameters(&self) -> Result<Parameters, ConfigError> {
+        self.prediction
+            .to_parameters()
+            .map_err(|source| ConfigError::Param {
+                section: Section::Prediction,
+                source,
+            })
+    }
+}
+
+/// Numbers and booleans as TOML scalars, everything else as a string.
+fn toml_value(value: String) -> toml::Value {
+    if let Ok(v) = value.parse::<i64>() {
+        toml::Value::Integer(v)
+    } else if let Some(v) = value.parse::<f64>().ok().filter(|v| v.is_finite()) {
+        toml::Value::Float(v)
+    } else if let Ok(v) = value.parse::<bool>() {
+        toml::Value::Boolean(v)
+    } else {
+        toml::Value::String(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[dataset]
+max_bin = 63
+
+[booster]
+objective = "regression"
+metric = ["l2", "l1"]
+num_leaves = 10
+
+[prediction]
+pred_early_stop = false
+"#;
+
+    #[test]
+    fn reads_toml_sections() {
+        let config = Config::from_toml_str(TOML).unwrap();
+        assert_eq!(config.dataset.to_string(), "max_bin=63");
+        assert_eq!(
+            config.booster.to_string(),
+            "objective=regression metric=l2,l1 num_leaves=10"
+        );
+        assert_eq!(config.prediction.to_string(), "pred_early_stop=false");
+        assert_eq!(
+            Config::from_toml_str(&config.to_toml_string()).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn reads_yaml_and_lightgbm_configs() {
+        let yaml = "booster:\n  objective: binary\n  eta: 0.1\nprediction:\n  raw_score: true\n";
+        let config = Config::from_yaml_str(yaml).unwrap();
+        assert_eq!(
+            config.booster.to_string(),
+            "objective=binary learning_rate=0.1"
+        );
+        assert_eq!(config.prediction.to_string(), "raw_score=true");
+        assert_eq!(Config::from_yaml_str("").unwrap(), Config::default());
+
+        let config = Config::from_lightgbm_str(
+            "# trained by hand\nobjective = binary\nmax_bin = 15 # coarse\n\npredict_raw_score = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.booster.to_string(), "objective=binary max_bin=15");
+        assert_eq!(config.prediction.to_string(), "predict_raw_score=true");
+        assert!(Config::from_lightgbm_str("objective").is_err());
+    }
+
+    #[test]
+    fn overrides_replace_earlier_values() {
+        let mut config = Config::from_toml_str(TOML).unwrap();
+        config.set(Section::Booster, "num_leaves=31").unwrap();
+        // Already set in the dataset section, so replaced there.
+        config.set(Section::Booster, "max_bins=255").unwrap();
+        config
+            .set(Section::Booster, "booster.min_data_in_leaf=5")
+            .unwrap();
+        // Prediction parameters go to their own section.
+        config
+            .set(Section::Booster, "pred_early_stop=true")
+            .unwrap();
+        config
+            .set(Section::Booster, "predict_raw_score=true")
+            .unwrap();
+        assert_eq!(config.dataset.to_string(), "max_bins=255");
+        assert_eq!(
+            config.booster.to_string(),
+            "objective=regression metric=l2,l1 num_leaves=31 min_data_in_leaf=5"
+        );
+        assert_eq!(
+            config.prediction.to_string(),
+            "pred_early_stop=true predict_raw_score=true"
+        );
+        assert_eq!(
+            config.training_params().unwrap().to_string(),
+            "objective=regression metric=l2,l1 num_leaves=31 min_data_in_leaf=5 max_bins=255"
+        );
+    }
+
+    #[test]
+    fn unknown_sections_and_keys_are_errors() {
+        assert!(matches!(
+            Config::from_toml_str("[model]\nnum_leaves = 3"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            Config::default().set(Section::Booster, "model.num_leaves=3"),
+            Err(ConfigError::UnknownSection(s)) if s == "model"
+        ));
+        let Err(ConfigError::Param { section, source }) =
+            Config::from_toml_str("[booster]\nnum_leafs = 3")
+        else {
+            panic!("unknown key accepted");
+        };
+        assert_eq!(section, Section::Booster);
+        assert_eq!(source, ParamError::new("num_leafs", "unknown parameter"));
+        assert!(
+            Config::default()
+                .set(Section::Booster, "num_leaves")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn keys_in_the_wrong_section_are_errors() {
+        for toml in [
+            "[prediction]\nnum_leaves = 3",
+            "[prediction]\nmax_bin = 3",
+            "[dataset]\nlearning_rate = 0.1",
+            "[dataset]\npredict_raw_score = true",
+            "[booster]\npred_early_stop = true",
+        ] {
+            assert!(Config::from_toml_str(toml).is_err(), "{toml} accepted");
+        }
+        assert!(
+            Config::default()
+                .set(Section::Prediction, "num_leaves=3")
+                .is_err()
+        );
+        let config =
+            Config::from_toml_str("[dataset]\nnum_threads = 2\n[prediction]\nnum_threads = 4")
+                .unwrap();
+        assert_eq!(config.prediction.num_threads, Some(4));
+        assert!(Config::from_toml_str("[booster]\nmax_bin = 3").is_ok());
+    }
+
+    #[test]
+    fn aliases_of_one_parameter_conflict() {
+        assert!(
+            Config::from_toml_str("[booster]\nearly_stopping_round = 5\nearly_stopping_rounds = 6")
+                .is_err()
+        );
+        assert!(Config::from_toml_str("[booster]\nnum_leaves = 5\nmax_leaves = 6").is_err());
+        assert!(
+            Config::from_toml_str("[dataset]\nmax_bin = 15\n[booster]\nmax_bins = 31")
+                .unwrap()
+                .training_params()
+                .is_err()
+        );
+        let mut config = Config::default();
+        config
+            .set(Section::Booster, "early_stopping_round=5")
+            .unwrap();
+        config.set(Section::Booster, "n_iter_no_change=6").unwrap();
+        assert_eq!(config.booster.to_string(), "n_iter_no_change=6");
+    }
+}
@@ -0,0 +1,689 @@
+use crate::{
+    EarlyStopping, EvalHistory, Evaluation, Metadata, Stop, Trainer, group_sizes, higher_is_better,
+};
+use lgbm::{FeatureData, Mat, Parameters, Result, mat::RowMajor};
+use std::{collections::HashMap, hash::Hash, io, thread};
+
+/// Assignment of rows to cross-validation folds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Folds {
+    num_folds: usize,
+    assignments: Vec<usize>,
+}
+
+impl Folds {
+    /// Splits `num_rows` rows into `num_folds` folds of nearly equal size, in
+    /// order or shuffled with `seed`.
+    pub fn k_fold(num_rows: usize, num_folds: usize, seed: Option<u64>) -> Result<Self> {
+        check_num_folds(num_folds, num_rows, "rows")?;
+        let mut order = (0..num_rows).collect::<Vec<_>>();
+        if let Some(seed) = seed {
+            SplitMix64::new(seed).shuffle(&mut order);
+        }
+        let mut assignments = vec![0; num_rows];
+        for (i, row) in order.into_iter().enumerate() {
+            assignments[row] = i % num_folds;
+        }
+        Ok(Self {
+            num_folds,
+            assignments,
+        })
+    }
+
+    /// Like [`Folds::k_fold`], but every distinct label is spread evenly over
+    /// the folds. `seed` shuffles the rows of every label and the order the
+    /// labels are dealt in, so it also matters when labels are all distinct.
+    pub fn stratified(labels: &[f32], num_folds: usize, seed: Option<u64>) -> Result<Self> {
+        check_num_folds(num_folds, labels.len(), "rows")?;
+        let mut strata = group_rows(labels.iter().map(|l| l.to_bits()));
+        if let Some(seed) = seed {
+            let mut rng = SplitMix64::new(seed);
+            rng.shuffle(&mut strata);
+            for rows in &mut strata {
+                rng.shuffle(rows);
+            }
+        }
+        let mut assignments = vec![0; labels.len()];
+        let mut next = 0;
+        for rows in strata {
+            // Continue dealing where the previous label stopped, so small
+            // classes do not all land in the first folds.
+            for row in rows {
+                assignments[row] = next % num_folds;
+                next += 1;
+            }
+        }
+        Ok(Self {
+            num_folds,
+            assignments,
+        })
+    }
+
+    /// Keeps all rows of a group in the same fold. Groups are assigned
+    /// largest first to the fold with the fewest rows; `seed` shuffles groups
+    /// of equal size.
+    pub fn grouped<G: Hash + Eq>(
+        groups: &[G],
+        num_folds: usize,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        let mut groups = group_rows(groups.iter());
+        check_num_folds(num_folds, groups.len(), "groups")?;
+        if let Some(seed) = seed {
+            SplitMix64::new(seed).shuffle(&mut groups);
+        }
+        groups.sort_by_key(|rows| std::cmp::Reverse(rows.len()));
+
+        let mut sizes = vec![0; num_folds];
+        let mut assignments = vec![0; groups.iter().map(Vec::len).sum()];
+        for rows in groups {
+            let fold = (0..num_folds).min_by_key(|&f| sizes[f]).unwrap();
+            sizes[fold] += rows.len();
+            for row in rows {
+                assignments[row] = fold;
+            }
+        }
+        Ok(Self {
+            num_folds,
+            assignments,
+        })
+    }
+
+    pub fn num_folds(&self) -> usize {
+        self.num_folds
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.assignments.len()
+    }
+
+    /// Fold of every row.
+    pub fn assignments(&self) -> &[usize] {
+        &self.assignments
+    }
+
+    /// `(train, valid)` row indices of fold `fold`.
+    pub fn split(&self, fold: usize) -> (Vec<usize>, Vec<usize>) {
+        (0..self.assignments.len()).partition(|&row| self.assignments[row] != fold)
+    }
+}
+
+fn check_num_folds(num_folds: usize, num_items: usize, items: &str) -> Result<()> {
+    if num_folds < 2 || num_folds > num_items {
+        return Err(lgbm::Error::from_message(&format!(
+            "cannot split {num_items} {items} into {num_folds} folds"
+        )));
+    }
+    Ok(())
+}
+
+/// Row indices per distinct key, in order of first appearance.
+fn group_rows<K: Hash + Eq>(keys: impl IntoIterator<Item = K>) -> Vec<Vec<usize>> {
+    let mut index = HashMap::new();
+    let mut groups = Vec::<Vec<usize>>::new();
+    for (row, key) in keys.into_iter().enumerate() {
+        let i = *index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[i].push(row);
+    }
+    groups
+}
+
+/// Small deterministic PRNG (SplitMix64) for shuffling rows and sampling.
+#[derive(Clone, Debug)]
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `0..n`; `n` must be positive.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize
+    }
+
+    pub(crate) fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Mean and standard deviation of a metric over the folds after one
+/// iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CvScore {
+    pub iteration: usize,
+    pub metric: String,
+    pub mean: f64,
+    pub std: f64,
+}
+
+/// Outcome of a [`CrossValidation`] run.
+#[derive(Clone, Debug)]
+pub struct CvResult {
+    scores: Vec<CvScore>,
+    metric: String,
+    best: (usize, f64),
+    folds: Vec<EvalHistory>,
+}
+
+impl CvResult {
+    #[cfg(test)]
+    pub(crate) fn from_best(metric: &str, best: (usize, f64)) -> Self {
+        Self {
+            scores: Vec::new(),
+            metric: metric.to_string(),
+            best,
+            folds: Vec::new(),
+        }
+    }
+
+    /// Scores of every metric, by iteration.
+    pub fn scores(&self) -> &[CvScore] {
+        &self.scores
+    }
+
+    /// Mean of one metric, indexed by iteration - 1.
+    pub fn mean(&self, metric: &str) -> Vec<f64> {
+        self.scores
+            .iter()
+            .filter(|s| s.metric == metric)
+            .map(|s| s.mean)
+            .collect()
+    }
+
+    /// Metric the best iteration was chosen by.
+    pub fn metric(&self) -> &str {
+        &self.metric
+    }
+
+    /// Optimal number of boosting rounds: the iteration with the best mean
+    /// of [`CvResult::metric`].
+    pub fn best_iteration(&self) -> usize {
+        self.best.0
+    }
+
+    /// Mean of [`CvResult::metric`] at the best iteration.
+    pub fn best_score(&self) -> f64 {
+        self.best.1
+    }
+
+    /// Validation history of every fold.
+    pub fn folds(&self) -> &[EvalHistory] {
+        &self.folds
+    }
+
+    /// Writes `iteration,metric,mean,std` rows with a header line.
+    pub fn write_csv(&self, mut w: impl io::Write) -> io::Result<()> {
+        writeln!(w, "iteration,metric,mean,std")?;
+        for s in &self.scores {
+            writeln!(w, "{},{},{},{}", s.iteration, s.metric, s.mean, s.std)?;
+        }
+        Ok(())
+    }
+}
+
+/// K-fold cross-validation: trains one booster per fold on the other folds
+/// and evaluates it on the held-out fold.
+///
+/// Every fold is boosted for the full number of iterations (or until no
+/// further splits are possible). Early stopping is then applied to the mean
+/// over the folds, which truncates the scores and picks the best iteration.
+/// Without early stopping the best iteration is that with the best mean of
+/// the first metric.
+///
+/// Ranking objectives need [`CrossValidation::groups`].
+pub struct CrossValidation<'a, T> {
+    features: Mat<'a, T, RowMajor>,
+    labels: &'a [f32],
+    weights: Option<&'a [f32]>,
+    groups: Option<&'a [i32]>,
+    early_stopping: Option<EarlyStopping>,
+    parallel: bool,
+}
+
+impl<'a, T: FeatureData + Copy + Sync> CrossValidation<'a, T> {
+    pub fn new(features: Mat<'a, T, RowMajor>, labels: &'a [f32]) -> Self {
+        Self {
+            features,
+            labels,
+            weights: None,
+            groups: None,
+            early_stopping: None,
+            parallel: false,
+        }
+    }
+
+    pub fn weights(mut self, weights: &'a [f32]) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Sizes of consecutive query groups, as for [`Metadata::set_groups`](crate::Metadata::set_groups).
+    ///
+    /// Every group must lie in one fold, e.g. with [`Folds::grouped`] on the
+    /// query ids; the datasets of each fold get the sizes of their groups.
+    pub fn groups(mut self, sizes: &'a [i32]) -> Self {
+        self.groups = Some(sizes);
+        self
+    }
+
+    /// Stops on the mean validation metric over the folds.
+    pub fn early_stopping(mut self, early_stopping: EarlyStopping) -> Self {
+        self.early_stopping = Some(early_stopping);
+        self
+    }
+
+    /// Trains the folds on one thread each. Consider lowering `num_threads`,
+    /// as every booster also runs LightGBM's own threads.
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn run(
+        &self,
+        folds: &Folds,
+        parameters: &Parameters,
+        num_iterations: usize,
+    ) -> Result<CvResult> {
+        let num_rows = self.features.nrow();
+        if self.labels.len() != num_rows || folds.num_rows() != num_rows {
+            return Err(lgbm::Error::from_message(&format!(
+                "{num_rows} rows, {} labels and {} fold assignments",
+                self.labels.len(),
+                folds.num_rows()
+            )));
+        }
+        if self.weights.is_some_and(|w| w.len() != num_rows) {
+            return Err(lgbm::Error::from_message("weights do not match the rows"));
+        }
+        let queries = self
+            .groups
+            .map(|sizes| query_of_rows(sizes, folds))
+            .transpose()?;
+        let queries = queries.as_deref();
+
+        let histories = if self.parallel {
+            thread::scope(|scope| {
+                let handles = (0..folds.num_folds())
+                    .map(|fold| {
+                        scope.spawn(move || {
+                            self.train_fold(folds, fold, queries, parameters, num_iterations)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().expect("fold training panicked"))
+                    .collect::<Result<Vec<_>>>()
+            })?
+        } else {
+            (0..folds.num_folds())
+                .map(|fold| self.train_fold(folds, fold, queries, parameters, num_iterations))
+                .collect::<Result<Vec<_>>>()?
+        };
+        self.aggregate(histories)
+    }
+
+    fn train_fold(
+        &self,
+        folds: &Folds,
+        fold: usize,
+        queries: Option<&[usize]>,
+        parameters: &Parameters,
+        num_iterations: usize,
+    ) -> Result<EvalHistory> {
+        let (train_rows, valid_rows) = folds.split(fold);
+        let (features, metadata) = self.subset(&train_rows, queries)?;
+        let ncol = self.features.ncol();
+        let features = Mat::from_slice(&features, train_rows.len(), ncol, RowMajor);
+        let mut trainer = Trainer::new(features, metadata, parameters)?;
+        let (features, metadata) = self.subset(&valid_rows, queries)?;
+        let features = Mat::from_slice(&features, valid_rows.len(), ncol, RowMajor);
+        trainer.add_valid("valid", features, metadata)?;
+        match trainer.train(num_iterations)? {
+            None | Some(Stop::NoSplits) => {}
+            Some(stop) => unreachable!("{stop:?} without early stopping or callbacks"),
+        }
+        Ok(trainer.history().clone())
+    }
+
+    /// Row-major features and metadata of `rows`.
+    fn subset(&self, rows: &[usize], queries: Option<&[usize]>) -> Result<(Vec<T>, Metadata)> {
+        let ncol = self.features.ncol();
+        let mut values = Vec::with_capacity(rows.len() * ncol);
+        for &row in rows {
+            values.extend_from_slice(self.features.row(row));
+        }
+        let mut metadata = Metadata::new(rows.len());
+        metadata.set_labels(rows.iter().map(|&r| self.labels[r]).collect())?;
+        if let Some(weights) = self.weights {
+            metadata.set_weights(rows.iter().map(|&r| weights[r]).collect())?;
+        }
+        if let Some(queries) = queries {
+            // `rows` are ascending, so the rows of each query stay consecutive.
+            let queries = rows.iter().map(|&r| queries[r]).collect::<Vec<_>>();
+            metadata.set_groups(group_sizes(&queries)?)?;
+        }
+        Ok((values, metadata))
+    }
+
+    fn aggregate(&self, folds: Vec<EvalHistory>) -> Result<CvResult> {
+        // Folds that ran out of splits stop early; score the iterations all
+        // folds reached.
+        let num_iterations = folds
+            .iter()
+            .map(|h| h.evaluations().last().map_or(0, |e| e.iteration))
+            .min()
+            .unwrap_or(0);
+        let metrics = folds[0]
+            .iteration(1)
+            .map(|e| e.metric.clone())
+            .collect::<Vec<_>>();
+        if num_iterations == 0 || metrics.is_empty() {
+            return Err(lgbm::Error::from_message(
+                "cross-validation produced no evaluations; is a metric set?",
+            ));
+        }
+        let values = metrics
+            .iter()
+            .map(|metric| folds.iter().map(|h| h.values("valid", metric)).collect())
+            .collect::<Vec<Vec<Vec<f64>>>>();
+
+        let mut early_stopping = self
+            .early_stopping
+            .clone()
+            .unwrap_or_else(|| EarlyStopping::new(usize::MAX));
+        let mut scores = Vec::new();
+        for iteration in 1..=num_iterations {
+            let start = scores.len();
+            for (metric, values) in metrics.iter().zip(&values) {
+                let fold_values = values.iter().map(|v| v[iteration - 1]);
+                let mean = fold_values.clone().sum::<f64>() / folds.len() as f64;
+                let variance =
+                    fold_values.map(|v| (v - mean).powi(2)).sum::<f64>() / folds.len() as f64;
+                scores.push(CvScore {
+                    iteration,
+                    metric: metric.clone(),
+                    mean,
+                    std: variance.sqrt(),
+                });
+            }
+            let means = scores[start..]
+                .iter()
+                .map(|s| Evaluation {
+                    iteration,
+                    dataset: "valid".to_string(),
+                    metric: s.metric.clone(),
+                    value: s.mean,
+                })
+                .collect::<Vec<_>>();
+            if early_stopping.update(iteration, &means, higher_is_better) {
+                break;
+            }
+        }
+
+        let (Some(best), Some(metric)) = (early_stopping.best(), early_stopping.watched_metric())
+        else {
+            return Err(lgbm::Error::from_message(&format!(
+                "early stopping metric is not one of {}",
+                metrics.join(", ")
+            )));
+        };
+        let metric = metric.to_string();
+        Ok(CvResult {
+            scores,
+            metric,
+            best,
+            folds,
+        })
+    }
+}
+
+/// Query index of every row for group `sizes`, checking that each query lies
+/// in one fold.
+fn query_of_rows(sizes: &[i32], folds: &Folds) -> Result<Vec<usize>> {
+    let mut queries = Vec::with_capacity(folds.num_rows());
+    for (query, &size) in sizes.iter().enumerate() {
+        let size = usize::try_from(size)
+            .ok()
+            .filter(|&s| s > 0)
+            .ok_or_else(|| lgbm::Error::from_message(&format!("group {query} has size {size}")))?;
+        queries.extend(std::iter::repeat_n(query, size));
+    }
+    if queries.len() != folds.num_rows() {
+        return Err(lgbm::Error::from_message(&format!(
+            "groups cover {} rows, but there are {}",
+            queries.len(),
+            folds.num_rows()
+        )));
+    }
+    let assignments = folds.assignments();
+    if let Some(row) = (1..queries.len())
+        .find(|&r| queries[r] == queries[r - 1] && assignments[r] != assignments[r - 1])
+    {
+        return Err(lgbm::Error::from_message(&format!(
+            "query group {} is split across folds; build the folds with `Folds::grouped` on the query ids",
+            queries[row]
+        )));
+    }
+    Ok(queries)
+}
+
+/// Cross-validates `parameters` with default [`CrossValidation`] settings.
+pub fn cv<T: FeatureData + Copy + Sync>(
+    features: Mat<'_, T, RowMajor>,
+    labels: &[f32],
+    folds: &Folds,
+    parameters: &Parameters,
+    num_iterations: usize,
+) -> Result<CvResult> {
+    CrossValidation::new(features, labels).run(folds, parameters, num_iterations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fold_sizes(folds: &Folds) -> Vec<usize> {
+        (0..folds.num_folds())
+            .map(|fold| folds.split(fold).1.len())
+            .collect()
+    }
+
+    #[test]
+    fn k_fold_balances_rows() {
+        let folds = Folds::k_fold(10, 3, None).unwrap();
+        assert_eq!(folds.assignments(), [0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(fold_sizes(&folds), [4, 3, 3]);
+
+        let shuffled = Folds::k_fold(10, 3, Some(7)).unwrap();
+        assert_eq!(fold_sizes(&shuffled), [4, 3, 3]);
+        assert_ne!(shuffled, folds);
+        assert_eq!(shuffled, Folds::k_fold(10, 3, Some(7)).unwrap());
+    }
+
+    #[test]
+    fn k_fold_rejects_bad_fold_counts() {
+        assert!(Folds::k_fold(10, 1, None).is_err());
+        assert!(Folds::k_fold(3, 4, None).is_err());
+        assert!(Folds::k_fold(3, 3, None).is_ok());
+    }
+
+    #[test]
+    fn split_partitions_rows() {
+        let folds = Folds::k_fold(7, 3, Some(1)).unwrap();
+        for fold in 0..3 {
+            let (train, valid) = folds.split(fold);
+            assert_eq!(train.len() + valid.len(), 7);
+            assert!(valid.iter().all(|&r| folds.assignments()[r] == fold));
+            assert!(train.iter().all(|&r| folds.assignments()[r] != fold));
+        }
+    }
+
+    #[test]
+    fn stratified_spreads_every_label() {
+        let labels = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        for seed in [None, Some(3)] {
+            let folds = Folds::stratified(&labels, 3, seed).unwrap();
+            for fold in 0..3 {
+                let (_, valid) = folds.split(fold);
+                let positives = valid.iter().filter(|&&r| labels[r] == 1.0).count();
+                assert_eq!((valid.len(), positives), (3, 1), "fold {fold}");
+            }
+        }
+    }
+
+    #[test]
+    fn stratified_continues_dealing_across_labels() {
+        // One row per label must not put every row in fold 0.
+        let folds = Folds::stratified(&[0.0, 1.0, 2.0, 3.0], 2, None).unwrap();
+        assert_eq!(folds.assignments(), [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn stratified_seed_shuffles_distinct_labels() {
+        let labels = (0..20).map(|i| i as f32 * 0.1).collect::<Vec<_>>();
+        let folds = |seed| Folds::stratified(&labels, 4, seed).unwrap();
+        assert_ne!(folds(Some(1)), folds(Some(2)));
+        assert_ne!(folds(Some(1)), folds(None));
+        assert_eq!(folds(Some(1)), folds(Some(1)));
+        assert_eq!(fold_sizes(&folds(Some(1))), [5; 4]);
+    }
+
+    #[test]
+    fn grouped_keeps_groups_together() {
+        let groups = ["a", "a", "a", "a", "b", "b", "c", "c", "d", "e"];
+        let folds = Folds::grouped(&groups, 2, Some(5)).unwrap();
+        for (row, group) in groups.iter().enumerate() {
+            for (other, _) in groups.iter().enumerate().filter(|(_, g)| *g == group) {
+                assert_eq!(folds.assignments()[row], folds.assignments()[other]);
+            }
+        }
+        assert_eq!(fold_sizes(&folds), [5, 5]);
+    }
+
+    #[test]
+    fn grouped_needs_a_group_per_fold() {
+        assert!(Folds::grouped(&[1, 1, 2, 2], 3, None).is_err());
+        assert!(Folds::grouped(&[1, 1, 2, 3], 3, None).is_ok());
+    }
+
+    #[test]
+    fn query_of_rows_needs_whole_groups_in_a_fold() {
+        let folds = Folds::grouped(&[0, 0, 1, 2, 2, 2], 2, None).unwrap();
+        assert_eq!(
+            query_of_rows(&[2, 1, 3], &folds).unwrap(),
+            [0, 0, 1, 2, 2, 2]
+        );
+        assert!(query_of_rows(&[2, 1, 2], &folds).is_err());
+        assert!(query_of_rows(&[2, 0, 1, 3], &folds).is_err());
+        // Groups (0, 1) and (2, 3) are each split by plain k-fold.
+        let folds = Folds::k_fold(4, 2, None).unwrap();
+        assert!(query_of_rows(&[2, 2], &folds).is_err());
+        assert!(query_of_rows(&[1, 1, 1, 1], &folds).is_ok());
+    }
+
+    fn history(values: &[(f64, f64)]) -> EvalHistory {
+        let evaluations = values
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &(l2, auc))| {
+                [("l2", l2), ("auc", auc)].map(|(metric, value)| Evaluation {
+                    iteration: i + 1,
+                    dataset: "valid".to_string(),
+                    metric: metric.to_string(),
+                    value,
+                })
+            })
+            .collect();
+        EvalHistory::from_evaluations(evaluations)
+    }
+
+    fn cross_validation(values: &[f64]) -> CrossValidation<'_, f64> {
+        CrossValidation::new(Mat::from_slice(values, values.len(), 1, RowMajor), &[])
+    }
+
+    #[test]
+    fn aggregate_averages_folds() {
+        let rows = [0.0];
+        let result = cross_validation(&rows)
+            .aggregate(vec![
+                history(&[(4.0, 0.5), (2.0, 0.6), (3.0, 0.7)]),
+                history(&[(2.0, 0.7), (1.0, 0.8), (2.0, 0.9)]),
+            ])
+            .unwrap();
+        assert_eq!(result.scores().len(), 6);
+        assert_eq!(
+            result.scores()[0],
+            CvScore {
+                iteration: 1,
+                metric: "l2".to_string(),
+                mean: 3.0,
+                std: 1.0,
+            }
+        );
+        assert_eq!(result.mean("l2"), [3.0, 1.5, 2.5]);
+        // The first metric picks the best iteration; lower l2 is better.
+        assert_eq!(result.metric(), "l2");
+        assert_eq!((result.best_iteration(), result.best_score()), (2, 1.5));
+        assert_eq!(result.folds().len(), 2);
+    }
+
+    #[test]
+    fn aggregate_stops_at_shortest_fold() {
+        let rows = [0.0];
+        let result = cross_validation(&rows)
+            .aggregate(vec![
+                history(&[(4.0, 0.5), (2.0, 0.6), (1.0, 0.7)]),
+                history(&[(2.0, 0.7), (1.0, 0.8)]),
+            ])
+            .unwrap();
+        assert_eq!(result.mean("l2"), [3.0, 1.5]);
+    }
+
+    #[test]
+    fn aggregate_applies_early_stopping_to_means() {
+        let rows = [0.0];
+        let result = cross_validation(&rows)
+            .early_stopping(EarlyStopping::new(1).metric("auc"))
+            .aggregate(vec![
+                history(&[(4.0, 0.5), (3.0, 0.8), (2.0, 0.7), (1.0, 0.9)]),
+                history(&[(4.0, 0.5), (3.0, 0.8), (2.0, 0.7), (1.0, 0.9)]),
+            ])
+            .unwrap();
+        assert_eq!(result.metric(), "auc");
+        assert_eq!(result.best_iteration(), 2);
+        assert_eq!(result.mean("auc"), [0.5, 0.8, 0.7]);
+    }
+
+    #[test]
+    fn aggregate_needs_evaluations() {
+        let rows = [0.0];
+        let cv = cross_validation(&rows);
+        assert!(cv.aggregate(vec![history(&[]), history(&[])]).is_err());
+        assert!(
+            cv.early_stopping(EarlyStopping::new(1).metric("ndcg"))
+                .aggregate(vec![history(&[(1.0, 0.5)])])
+                .is_err()
+        );
+    }
+}
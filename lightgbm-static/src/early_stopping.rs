@@ -0,0 +1,227 @@
+use crate::{Evaluation, params::parse_metrics};
+
+/// Stops training when a validation metric has not improved for `patience`
+/// iterations.
+///
+/// By default the first metric on the first validation dataset is watched,
+/// and whether higher is better is derived from the metric name, or taken
+/// from the [`Metric`](crate::Metric) for custom metrics.
+#[derive(Clone, Debug)]
+pub struct EarlyStopping {
+    patience: usize,
+    min_delta: f64,
+    metric: Option<String>,
+    dataset: Option<String>,
+    higher_is_better: Option<bool>,
+    rollback: bool,
+    best: Option<(usize, f64)>,
+}
+
+impl EarlyStopping {
+    /// `patience` is clamped to at least one iteration.
+    pub fn new(patience: usize) -> Self {
+        Self {
+            patience: patience.max(1),
+            min_delta: 0.0,
+            metric: None,
+            dataset: None,
+            higher_is_better: None,
+            rollback: false,
+            best: None,
+        }
+    }
+
+    /// Minimum change that counts as an improvement.
+    pub fn min_delta(mut self, min_delta: f64) -> Self {
+        self.min_delta = min_delta.abs();
+        self
+    }
+
+    /// Metric to watch, as named by LightGBM (e.g. `l2`, `ndcg@5`).
+    pub fn metric(mut self, metric: impl Into<String>) -> Self {
+        self.metric = Some(metric.into());
+        self
+    }
+
+    /// Validation dataset to watch.
+    pub fn dataset(mut self, dataset: impl Into<String>) -> Self {
+        self.dataset = Some(dataset.into());
+        self
+    }
+
+    /// Overrides the direction derived from the metric name.
+    pub fn higher_is_better(mut self, higher_is_better: bool) -> Self {
+        self.higher_is_better = Some(higher_is_better);
+        self
+    }
+
+    /// Roll the booster back to the best iteration when stopping, instead of
+    /// only recording it.
+    pub fn rollback(mut self, rollback: bool) -> Self {
+        self.rollback = rollback;
+        self
+    }
+
+    pub(crate) fn rolls_back(&self) -> bool {
+        self.rollback
+    }
+
+    /// Metric being watched; known after the first evaluation.
+    pub fn watched_metric(&self) -> Option<&str> {
+        self.metric.as_deref()
+    }
+
+    /// Best `(iteration, value)` seen so far.
+    pub fn best(&self) -> Option<(usize, f64)> {
+        self.best
+    }
+
+    /// Records the evaluations of `iteration`; returns `true` when training
+    /// should stop. `direction` tells whether higher is better for a metric.
+    pub(crate) fn update(
+        &mut self,
+        iteration: usize,
+        evaluations: &[Evaluation],
+        direction: impl FnOnce(&str) -> bool,
+    ) -> bool {
+        let Some(e) = evaluations.iter().find(|e| {
+            e.dataset != "training"
+                && self.dataset.as_ref().is_none_or(|d| *d == e.dataset)
+                && self.metric.as_ref().is_none_or(|m| *m == e.metric)
+        }) else {
+            return false;
+        };
+        // Pin the first match so later iterations compare like with like.
+        self.dataset.get_or_insert_with(|| e.dataset.clone());
+        self.metric.get_or_insert_with(|| e.metric.clone());
+        let higher_is_better = *self
+            .higher_is_better
+            .get_or_insert_with(|| direction(&e.metric));
+
+        let improved = match self.best {
+            None => true,
+            Some((_, best)) if higher_is_better => e.value > best + self.min_delta,
+            Some((_, best)) => e.value < best - self.min_delta,
+        };
+        if improved {
+            self.best = Some((iteration, e.value));
+        }
+        let (best_iteration, _) = self.best.unwrap();
+        iteration - best_iteration >= self.patience
+    }
+}
+
+/// Direction of a LightGBM metric such as `auc` or `ndcg@5`. Unknown metrics
+/// are treated as losses.
+pub fn higher_is_better(metric: &str) -> bool {
+    let name = metric.split('@').next().unwrap_or_default();
+    parse_metrics(name)
+        .ok()
+        .and_then(|metrics| metrics.first().copied())
+        .is_some_and(|m| !m.is_lower_is_better())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(iteration: usize, dataset: &str, metric: &str, value: f64) -> Evaluation {
+        Evaluation {
+            iteration,
+            dataset: dataset.to_string(),
+            metric: metric.to_string(),
+            value,
+        }
+    }
+
+    /// Feeds one `l2` value per iteration on `valid`; returns the iteration
+    /// training stopped at.
+    fn run(early_stopping: &mut EarlyStopping, values: &[f64]) -> Option<usize> {
+        (1..=values.len()).find(|&i| {
+            early_stopping.update(
+                i,
+                &[eval(i, "valid", "l2", values[i - 1])],
+                higher_is_better,
+            )
+        })
+    }
+
+    #[test]
+    fn stops_after_patience_without_improvement() {
+        let mut es = EarlyStopping::new(2);
+        assert_eq!(run(&mut es, &[0.5, 0.4, 0.45, 0.41, 0.3]), Some(4));
+        assert_eq!(es.best(), Some((2, 0.4)));
+        assert_eq!(es.watched_metric(), Some("l2"));
+    }
+
+    #[test]
+    fn min_delta_ignores_small_improvements() {
+        let mut es = EarlyStopping::new(2).min_delta(0.05);
+        assert_eq!(run(&mut es, &[0.5, 0.47, 0.46, 0.3]), Some(3));
+        assert_eq!(es.best(), Some((1, 0.5)));
+    }
+
+    #[test]
+    fn direction_follows_metric() {
+        let mut es = EarlyStopping::new(1);
+        let auc = |i, v| [eval(i, "valid", "auc", v)];
+        assert!(!es.update(1, &auc(1, 0.7), higher_is_better));
+        assert!(!es.update(2, &auc(2, 0.8), higher_is_better));
+        assert!(es.update(3, &auc(3, 0.75), higher_is_better));
+        assert_eq!(es.best(), Some((2, 0.8)));
+
+        // Overridden direction wins over the metric name.
+        let mut es = EarlyStopping::new(1).higher_is_better(true);
+        assert_eq!(run(&mut es, &[0.1, 0.2, 0.1]), Some(3));
+        assert_eq!(es.best(), Some((2, 0.2)));
+
+        // The callback is used for metrics LightGBM does not know.
+        let mut es = EarlyStopping::new(1);
+        let score = |i, v| [eval(i, "valid", "my_score", v)];
+        assert!(!es.update(1, &score(1, 1.0), |m| m == "my_score"));
+        assert!(es.update(2, &score(2, 0.5), |_| unreachable!("direction is pinned")));
+    }
+
+    #[test]
+    fn watches_first_validation_metric_unless_chosen() {
+        let evaluations = |i, a, b| {
+            [
+                eval(i, "training", "l2", 0.0),
+                eval(i, "valid", "l1", a),
+                eval(i, "valid", "l2", b),
+                eval(i, "test", "l1", b),
+            ]
+        };
+        let mut es = EarlyStopping::new(1);
+        assert!(!es.update(1, &evaluations(1, 1.0, 1.0), higher_is_better));
+        assert!(es.update(2, &evaluations(2, 1.0, 0.5), higher_is_better));
+        assert_eq!(es.watched_metric(), Some("l1"));
+
+        let mut es = EarlyStopping::new(1).metric("l2");
+        assert!(!es.update(1, &evaluations(1, 1.0, 1.0), higher_is_better));
+        assert!(!es.update(2, &evaluations(2, 1.0, 0.5), higher_is_better));
+        assert_eq!(es.best(), Some((2, 0.5)));
+
+        let mut es = EarlyStopping::new(1).dataset("test");
+        assert!(!es.update(1, &evaluations(1, 1.0, 1.0), higher_is_better));
+        assert!(!es.update(2, &evaluations(2, 1.0, 0.5), higher_is_better));
+        assert_eq!(es.best(), Some((2, 0.5)));
+    }
+
+    #[test]
+    fn missing_metric_never_stops() {
+        let mut es = EarlyStopping::new(1).metric("auc");
+        assert_eq!(run(&mut es, &[0.5, 0.6, 0.7]), None);
+        assert_eq!(es.best(), None);
+    }
+
+    #[test]
+    fn metric_directions() {
+        assert!(higher_is_better("auc"));
+        assert!(higher_is_better("ndcg@5"));
+        assert!(higher_is_better("map@3"));
+        assert!(!higher_is_better("l2"));
+        assert!(!higher_is_better("binary_logloss"));
+        assert!(!higher_is_better("something_else"));
+    }
+}
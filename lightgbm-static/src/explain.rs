@@ -0,0 +1,198 @@
+use crate::Predictions;
+use lgbm::{AsMat, Booster, FeatureData, Parameters, PredictType, Result};
+use std::sync::Arc;
+
+/// SHAP values of one class of one prediction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassExplanation {
+    /// Expected raw score over the training data.
+    pub base_value: f64,
+    /// Contribution of every feature to the raw score.
+    pub contributions: Vec<f64>,
+}
+
+impl ClassExplanation {
+    /// Base value plus all contributions; equals the raw score.
+    pub fn raw_score(&self) -> f64 {
+        self.base_value + self.contributions.iter().sum::<f64>()
+    }
+}
+
+/// SHAP explanation of one prediction, from `C_API_PREDICT_CONTRIB`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    feature_names: Arc<[String]>,
+    classes: Vec<ClassExplanation>,
+}
+
+impl Explanation {
+    /// Splits contribution predictions into one explanation per row.
+    pub fn from_predictions(
+        predictions: &Predictions,
+        feature_names: &[String],
+    ) -> Result<Vec<Self>> {
+        if predictions.predict_type() != PredictType::Contrib {
+            return Err(lgbm::Error::from_message(
+                "explanations need `PredictType::Contrib` predictions",
+            ));
+        }
+        let per_class = feature_names.len() + 1;
+        if predictions.num_cols() != per_class * predictions.num_class() {
+            return Err(lgbm::Error::from_message(&format!(
+                "{} contributions per row for {} features and {} classes",
+                predictions.num_cols(),
+                feature_names.len(),
+                predictions.num_class()
+            )));
+        }
+        let feature_names = Arc::<[String]>::from(feature_names);
+        Ok(predictions
+            .rows()
+            .map(|row| Explanation {
+                feature_names: feature_names.clone(),
+                classes: row
+                    .chunks_exact(per_class)
+                    .map(|values| ClassExplanation {
+                        base_value: values[per_class - 1],
+                        contributions: values[..per_class - 1].to_vec(),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    pub fn num_class(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Explanation of `class`; use 0 unless multiclass.
+    pub fn class(&self, class: usize) -> &ClassExplanation {
+        &self.classes[class]
+    }
+
+    pub fn classes(&self) -> &[ClassExplanation] {
+        &self.classes
+    }
+
+    /// `(feature name, contribution)` of `class`, largest absolute impact
+    /// first.
+    pub fn by_impact(&self, class: usize) -> Vec<(&str, f64)> {
+        let mut features = self
+            .feature_names
+            .iter()
+            .map(String::as_str)
+            .zip(self.classes[class].contributions.iter().copied())
+            .collect::<Vec<_>>();
+        features.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        features
+    }
+
+    /// Checks that base value plus contributions reproduce `raw_scores`, one
+    /// per class, within `tolerance`.
+    pub fn verify(&self, raw_scores: &[f64], tolerance: f64) -> Result<()> {
+        if raw_scores.len() != self.classes.len() {
+            return Err(lgbm::Error::from_message(&format!(
+                "{} raw scores for {} classes",
+                raw_scores.len(),
+                self.classes.len()
+            )));
+        }
+        for (class, (explanation, &raw_score)) in self.classes.iter().zip(raw_scores).enumerate() {
+            let sum = explanation.raw_score();
+            if (sum - raw_score).abs() > tolerance {
+                return Err(lgbm::Error::from_message(&format!(
+                    "contributions of class {class} sum to {sum}, but the raw score is {raw_score}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Explains every row of `mat` with SHAP values, with the model's feature
+/// names attached.
+pub fn explain<T: FeatureData>(
+    booster: &Booster,
+    mat: impl AsMat<T>,
+    parameters: &Parameters,
+) -> Result<Vec<Explanation>> {
+    let predictions =
+        Predictions::predict(booster, mat, PredictType::Contrib, 0, None, parameters)?;
+    Explanation::from_predictions(&predictions, &booster.get_feature_names()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    fn explain(values: Vec<f64>, num_rows: usize, num_class: usize) -> Result<Vec<Explanation>> {
+        let predictions =
+            Predictions::from_values(values, num_rows, num_class, PredictType::Contrib)?;
+        Explanation::from_predictions(&predictions, &names())
+    }
+
+    #[test]
+    fn splits_rows_into_features_and_bias_per_class() {
+        // Per row and class: contribution of `a`, of `b`, then the bias.
+        let explanations = explain(
+            vec![
+                1.0, 2.0, 0.5, -1.0, 0.0, 0.25, //
+                3.0, 4.0, 0.5, 0.0, -2.0, 0.25,
+            ],
+            2,
+            2,
+        )
+        .unwrap();
+        assert_eq!(explanations.len(), 2);
+        let first = &explanations[0];
+        assert_eq!(first.feature_names(), ["a", "b"]);
+        assert_eq!(first.num_class(), 2);
+        assert_eq!(
+            first.classes(),
+            [
+                ClassExplanation {
+                    base_value: 0.5,
+                    contributions: vec![1.0, 2.0]
+                },
+                ClassExplanation {
+                    base_value: 0.25,
+                    contributions: vec![-1.0, 0.0]
+                },
+            ]
+        );
+        assert_eq!(explanations[1].class(1).contributions, [0.0, -2.0]);
+        assert_eq!(explanations[1].class(0).raw_score(), 7.5);
+    }
+
+    #[test]
+    fn rejects_other_prediction_types_and_shapes() {
+        let normal = Predictions::from_values(vec![0.0; 3], 1, 1, PredictType::Normal).unwrap();
+        assert!(Explanation::from_predictions(&normal, &names()).is_err());
+        // Four values per row do not fit two features and a bias.
+        assert!(explain(vec![0.0; 4], 1, 1).is_err());
+    }
+
+    #[test]
+    fn by_impact_sorts_by_absolute_value() {
+        let explanation = &explain(vec![0.5, -2.0, 1.0], 1, 1).unwrap()[0];
+        assert_eq!(explanation.by_impact(0), [("b", -2.0), ("a", 0.5)]);
+    }
+
+    #[test]
+    fn verify_checks_the_sum_within_tolerance() {
+        let explanation = &explain(vec![0.5, -2.0, 1.0, 1.0, 1.0, 0.0], 1, 2).unwrap()[0];
+        explanation.verify(&[-0.5, 2.0], 0.0).unwrap();
+        explanation.verify(&[-0.5 + 1e-7, 2.0], 1e-6).unwrap();
+        assert!(explanation.verify(&[-0.5 + 1e-3, 2.0], 1e-6).is_err());
+        assert!(explanation.verify(&[-0.5, 2.5], 1e-6).is_err());
+        assert!(explanation.verify(&[-0.5], 1e-6).is_err());
+    }
+}
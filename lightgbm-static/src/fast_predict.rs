@@ -0,0 +1,120 @@
+use crate::ffi::check;
+use lgbm::{Booster, FeatureData, FeatureImportanceType, Parameters, PredictType, Result};
+use lightgbm_static_sys::{
+    BoosterHandle, FastConfigHandle, LGBM_BoosterFree, LGBM_BoosterLoadModelFromString,
+    LGBM_BoosterPredictForMatSingleRowFast, LGBM_BoosterPredictForMatSingleRowFastInit,
+    LGBM_FastConfigFree,
+};
+use std::{marker::PhantomData, os::raw::c_int, ptr};
+
+/// Single-row predictor on LightGBM's fast config
+/// (`LGBM_BoosterPredictForMatSingleRowFastInit`).
+///
+/// Prediction type, iterations, parameters and the output buffer are set up
+/// once, so [`FastPredictor::predict`] does not allocate. Rows are `&[T]`
+/// with one value per feature of the model.
+///
+/// The predictor holds its own copy of the model, loaded from the booster's
+/// model string, and stays valid after the booster is dropped or trained
+/// further.
+pub struct FastPredictor<T> {
+    booster: BoosterHandle,
+    config: FastConfigHandle,
+    num_feature: usize,
+    output: Box<[f64]>,
+    _data: PhantomData<fn(&[T])>,
+}
+
+// The handles are only used through `&mut self`.
+unsafe impl<T> Send for FastPredictor<T> {}
+
+impl<T: FeatureData> FastPredictor<T> {
+    /// Prepares predictions of `predict_type`; iterations are chosen as for
+    /// [`Booster::predict_for_mat`].
+    pub fn new(
+        booster: &Booster,
+        predict_type: PredictType,
+        start_iteration: usize,
+        num_iteration: Option<usize>,
+        parameters: &Parameters,
+    ) -> Result<Self> {
+        let num_feature = booster.get_num_feature()?;
+        let num_predict =
+            booster.calc_num_predict(1, predict_type, start_iteration, num_iteration)?;
+        let model = booster.save_model_to_string(0, None, FeatureImportanceType::Split)?;
+
+        let mut handle = ptr::null_mut();
+        let mut num_iterations = 0;
+        unsafe {
+            check(LGBM_BoosterLoadModelFromString(
+                model.as_ptr(),
+                &mut num_iterations,
+                &mut handle,
+            ))?;
+        }
+        // From here on, `Drop` frees the handles on error.
+        let mut predictor = Self {
+            booster: handle,
+            config: ptr::null_mut(),
+            num_feature,
+            output: vec![0.0; num_predict].into_boxed_slice(),
+            _data: PhantomData,
+        };
+        unsafe {
+            check(LGBM_BoosterPredictForMatSingleRowFastInit(
+                predictor.booster,
+                predict_type as u32 as c_int,
+                start_iteration.try_into()?,
+                num_iteration.unwrap_or(0).try_into()?,
+                T::DATA_TYPE,
+                num_feature.try_into()?,
+                parameters.to_cstring()?.as_ptr(),
+                &mut predictor.config,
+            ))?;
+        }
+        Ok(predictor)
+    }
+
+    /// Number of values expected per row.
+    pub fn num_feature(&self) -> usize {
+        self.num_feature
+    }
+
+    /// Number of values returned per row.
+    pub fn num_outputs(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Predicts `row`. The result is overwritten by the next call.
+    pub fn predict(&mut self, row: &[T]) -> Result<&[f64]> {
+        if row.len() != self.num_feature {
+            return Err(lgbm::Error::from_message(&format!(
+                "row size must be {}, but got {}",
+                self.num_feature,
+                row.len()
+            )));
+        }
+        let mut out_len = 0;
+        unsafe {
+            check(LGBM_BoosterPredictForMatSingleRowFast(
+                self.config,
+                T::as_data_ptr(row.as_ptr()),
+                &mut out_len,
+                self.output.as_mut_ptr(),
+            ))?;
+        }
+        debug_assert_eq!(out_len as usize, self.output.len());
+        Ok(&self.output)
+    }
+}
+
+impl<T> Drop for FastPredictor<T> {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.config.is_null() {
+                LGBM_FastConfigFree(self.config);
+            }
+            LGBM_BoosterFree(self.booster);
+        }
+    }
+}
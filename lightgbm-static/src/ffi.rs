@@ -0,0 +1,320 @@
+//! Helpers for calling the C API of [`lightgbm_static_sys`] directly, for what
+//! [`lgbm`] does not wrap.
+
+use lgbm::{AsMat, Data, FeatureData, Parameters, Result, mat::MatLayouts};
+use lightgbm_static_sys::{
+    BoosterHandle, C_API_FEATURE_IMPORTANCE_SPLIT, DatasetHandle, LGBM_BoosterAddValidData,
+    LGBM_BoosterCreate, LGBM_BoosterFree, LGBM_BoosterGetCurrentIteration, LGBM_BoosterGetEval,
+    LGBM_BoosterGetEvalCounts, LGBM_BoosterGetEvalNames, LGBM_BoosterGetNumPredict,
+    LGBM_BoosterGetPredict, LGBM_BoosterLoadModelFromString, LGBM_BoosterMerge,
+    LGBM_BoosterResetParameter, LGBM_BoosterRollbackOneIter, LGBM_BoosterSaveModelToString,
+    LGBM_BoosterUpdateOneIter, LGBM_BoosterUpdateOneIterCustom, LGBM_DatasetCreateFromMat,
+    LGBM_DatasetFree, LGBM_DatasetGetNumData, LGBM_DatasetSetFeatureNames, LGBM_DatasetSetField,
+    LGBM_GetLastError,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    ptr,
+};
+
+/// Turns a C API return code into the message of `LGBM_GetLastError`.
+pub(crate) fn check(code: c_int) -> Result<()> {
+    if code == 0 {
+        return Ok(());
+    }
+    let message = unsafe { CStr::from_ptr(LGBM_GetLastError()) };
+    Err(lgbm::Error::from_message(&message.to_string_lossy()))
+}
+
+fn to_cstring(s: &str) -> Result<CString> {
+    CString::new(s).map_err(lgbm::Error::from_error)
+}
+
+/// Owned `DatasetHandle`. Unlike [`lgbm::Dataset`], its handle can be passed
+/// to a [`RawBooster`]. Freed on drop.
+pub(crate) struct RawDataset(DatasetHandle);
+
+impl RawDataset {
+    /// `LGBM_DatasetCreateFromMat`; `reference` shares its bin mappers.
+    pub(crate) fn from_mat<T: FeatureData>(
+        mat: impl AsMat<T>,
+        reference: Option<&RawDataset>,
+        parameters: &Parameters,
+    ) -> Result<Self> {
+        let mat = mat.as_mat();
+        let mut handle = ptr::null_mut();
+        unsafe {
+            check(LGBM_DatasetCreateFromMat(
+                T::as_data_ptr(mat.as_ptr()),
+                T::DATA_TYPE,
+                mat.nrow().try_into()?,
+                mat.ncol().try_into()?,
+                c_int::from(mat.layout() == MatLayouts::RowMajor),
+                parameters.to_cstring()?.as_ptr(),
+                reference.map_or(ptr::null_mut(), |r| r.0),
+                &mut handle,
+            ))?;
+        }
+        Ok(Self(handle))
+    }
+
+    pub(crate) fn num_data(&self) -> Result<usize> {
+        let mut num_data = 0;
+        unsafe {
+            check(LGBM_DatasetGetNumData(self.0, &mut num_data))?;
+        }
+        Ok(usize::try_from(num_data)?)
+    }
+
+    /// `LGBM_DatasetSetField`, e.g. `c"label"`.
+    pub(crate) fn set_field<T: Data>(&mut self, name: &CStr, data: &[T]) -> Result<()> {
+        unsafe {
+            check(LGBM_DatasetSetField(
+                self.0,
+                name.as_ptr(),
+                T::as_data_ptr(data.as_ptr()),
+                data.len().try_into()?,
+                T::DATA_TYPE,
+            ))
+        }
+    }
+
+    pub(crate) fn set_feature_names(&mut self, names: &[String]) -> Result<()> {
+        let names = names
+            .iter()
+            .map(|name| to_cstring(name))
+            .collect::<Result<Vec<_>>>()?;
+        let mut ptrs = names.iter().map(|name| name.as_ptr()).collect::<Vec<_>>();
+        unsafe {
+            check(LGBM_DatasetSetFeatureNames(
+                self.0,
+                ptrs.as_mut_ptr(),
+                ptrs.len().try_into()?,
+            ))
+        }
+    }
+}
+
+impl Drop for RawDataset {
+    fn drop(&mut self) {
+        unsafe {
+            LGBM_DatasetFree(self.0);
+        }
+    }
+}
+
+/// Owned `BoosterHandle`, for C API calls on boosters [`lgbm`] does not
+/// expose. Freed on drop.
+///
+/// A booster created for training keeps pointers to its datasets: they must
+/// outlive it.
+pub(crate) struct RawBooster(BoosterHandle);
+
+impl RawBooster {
+    /// `LGBM_BoosterCreate` on the training dataset `train`.
+    pub(crate) fn new(train: &RawDataset, parameters: &Parameters) -> Result<Self> {
+        let mut handle = ptr::null_mut();
+        unsafe {
+            check(LGBM_BoosterCreate(
+                train.0,
+                parameters.to_cstring()?.as_ptr(),
+                &mut handle,
+            ))?;
+        }
+        Ok(Self(handle))
+    }
+
+    /// `LGBM_BoosterLoadModelFromString`.
+    pub(crate) fn from_string(model: &str) -> Result<Self> {
+        let model = to_cstring(model)?;
+        let mut handle = ptr::null_mut();
+        let mut num_iterations = 0;
+        unsafe {
+            check(LGBM_BoosterLoadModelFromString(
+                model.as_ptr(),
+                &mut num_iterations,
+                &mut handle,
+            ))?;
+        }
+        Ok(Self(handle))
+    }
+
+    /// `LGBM_BoosterAddValidData`; `valid` gets the next data index.
+    pub(crate) fn add_valid(&mut self, valid: &RawDataset) -> Result<()> {
+        unsafe { check(LGBM_BoosterAddValidData(self.0, valid.0)) }
+    }
+
+    pub(crate) fn reset_parameter(&mut self, parameters: &Parameters) -> Result<()> {
+        unsafe {
+            check(LGBM_BoosterResetParameter(
+                self.0,
+                parameters.to_cstring()?.as_ptr(),
+            ))
+        }
+    }
+
+    /// `LGBM_BoosterUpdateOneIter`; `true` when no further splits are
+    /// possible.
+    pub(crate) fn update_one_iter(&mut self) -> Result<bool> {
+        let mut is_finished = 0;
+        unsafe {
+            check(LGBM_BoosterUpdateOneIter(self.0, &mut is_finished))?;
+        }
+        Ok(is_finished != 0)
+    }
+
+    /// `LGBM_BoosterUpdateOneIterCustom`; the lengths of `grad` and `hess`
+    /// must match the training scores.
+    pub(crate) fn update_one_iter_custom(&mut self, grad: &[f32], hess: &[f32]) -> Result<bool> {
+        let mut is_finished = 0;
+        unsafe {
+            check(LGBM_BoosterUpdateOneIterCustom(
+                self.0,
+                grad.as_ptr(),
+                hess.as_ptr(),
+                &mut is_finished,
+            ))?;
+        }
+        Ok(is_finished != 0)
+    }
+
+    pub(crate) fn rollback_one_iter(&mut self) -> Result<()> {
+        unsafe { check(LGBM_BoosterRollbackOneIter(self.0)) }
+    }
+
+    pub(crate) fn current_iteration(&self) -> Result<usize> {
+        let mut iteration = 0;
+        unsafe {
+            check(LGBM_BoosterGetCurrentIteration(self.0, &mut iteration))?;
+        }
+        Ok(usize::try_from(iteration)?)
+    }
+
+    /// Names of LightGBM's metrics, in the order of [`RawBooster::eval`].
+    pub(crate) fn eval_names(&self) -> Result<Vec<String>> {
+        // The first call reports the number of names and the buffer size.
+        let (mut len, mut buffer_len) = (0, 0);
+        unsafe {
+            check(LGBM_BoosterGetEvalNames(
+                self.0,
+                0,
+                &mut len,
+                0,
+                &mut buffer_len,
+                ptr::null_mut(),
+            ))?;
+        }
+        let mut buffers = vec![vec![0u8; buffer_len]; usize::try_from(len)?];
+        let mut ptrs = buffers
+            .iter_mut()
+            .map(|b| b.as_mut_ptr().cast::<c_char>())
+            .collect::<Vec<_>>();
+        unsafe {
+            check(LGBM_BoosterGetEvalNames(
+                self.0,
+                ptrs.len().try_into()?,
+                &mut len,
+                buffer_len,
+                &mut buffer_len,
+                ptrs.as_mut_ptr(),
+            ))?;
+        }
+        buffers.truncate(usize::try_from(len)?);
+        buffers
+            .iter()
+            .map(|b| {
+                CStr::from_bytes_until_nul(b)
+                    .map_err(lgbm::Error::from_error)
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+
+    /// `LGBM_BoosterGetEval` of dataset `data_idx`: 0 for training, then
+    /// validation datasets in the order they were added.
+    pub(crate) fn eval(&self, data_idx: usize) -> Result<Vec<f64>> {
+        let mut count = 0;
+        unsafe {
+            check(LGBM_BoosterGetEvalCounts(self.0, &mut count))?;
+        }
+        let mut results = vec![0.0; usize::try_from(count)?];
+        let mut len = 0;
+        unsafe {
+            check(LGBM_BoosterGetEval(
+                self.0,
+                data_idx.try_into()?,
+                &mut len,
+                results.as_mut_ptr(),
+            ))?;
+        }
+        results.truncate(usize::try_from(len)?);
+        Ok(results)
+    }
+
+    /// `LGBM_BoosterGetPredict`: current scores of dataset `data_idx`, all
+    /// rows of the first class, then all rows of the next.
+    pub(crate) fn predictions(&self, data_idx: usize) -> Result<Vec<f64>> {
+        let mut num_predict = 0;
+        unsafe {
+            check(LGBM_BoosterGetNumPredict(
+                self.0,
+                data_idx.try_into()?,
+                &mut num_predict,
+            ))?;
+        }
+        let mut values = vec![0.0; usize::try_from(num_predict)?];
+        let mut len = 0;
+        unsafe {
+            check(LGBM_BoosterGetPredict(
+                self.0,
+                data_idx.try_into()?,
+                &mut len,
+                values.as_mut_ptr(),
+            ))?;
+        }
+        values.truncate(usize::try_from(len)?);
+        Ok(values)
+    }
+
+    /// Puts the trees of `other` before those of `self` (`LGBM_BoosterMerge`).
+    pub(crate) fn merge(&mut self, other: &RawBooster) -> Result<()> {
+        unsafe { check(LGBM_BoosterMerge(self.0, other.0)) }
+    }
+
+    /// `LGBM_BoosterSaveModelToString` with split importances; `None` saves
+    /// every iteration.
+    pub(crate) fn save_model_to_string(&self, num_iteration: Option<usize>) -> Result<String> {
+        let num_iteration = num_iteration.unwrap_or(0).try_into()?;
+        let mut buffer = Vec::<u8>::new();
+        let mut len = 0;
+        // The first call reports the size, including the trailing NUL.
+        loop {
+            unsafe {
+                check(LGBM_BoosterSaveModelToString(
+                    self.0,
+                    0,
+                    num_iteration,
+                    C_API_FEATURE_IMPORTANCE_SPLIT as c_int,
+                    buffer.len().try_into()?,
+                    &mut len,
+                    buffer.as_mut_ptr().cast(),
+                ))?;
+            }
+            let len = usize::try_from(len)?;
+            if len <= buffer.len() {
+                buffer.truncate(len.saturating_sub(1));
+                return String::from_utf8(buffer).map_err(lgbm::Error::from_error);
+            }
+            buffer.resize(len, 0);
+        }
+    }
+}
+
+impl Drop for RawBooster {
+    fn drop(&mut self) {
+        unsafe {
+            LGBM_BoosterFree(self.0);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::Predictions;
+use lgbm::{AsMat, Booster, FeatureData, FeatureImportanceType, Parameters, PredictType, Result};
+use std::io;
+
+/// Leaf reached in every tree, as a rows × trees matrix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeafIndices {
+    num_rows: usize,
+    /// Index in the model of the first predicted tree.
+    first_tree: usize,
+    num_trees: usize,
+    leaves: Vec<u32>,
+}
+
+impl LeafIndices {
+    /// Predicts the leaves of `mat`; iterations are chosen as for
+    /// [`Booster::predict_for_mat`].
+    pub fn predict<T: FeatureData>(
+        booster: &Booster,
+        mat: impl AsMat<T>,
+        start_iteration: usize,
+        num_iteration: Option<usize>,
+        parameters: &Parameters,
+    ) -> Result<Self> {
+        let predictions = Predictions::predict(
+            booster,
+            mat,
+            PredictType::LeafIndex,
+            start_iteration,
+            num_iteration,
+            parameters,
+        )?;
+        let first_tree = start_iteration * booster.num_model_per_iteration()?;
+        Self::from_predictions(&predictions, first_tree)
+    }
+
+    /// Converts leaf index predictions whose first column is tree
+    /// `first_tree` of the model.
+    pub fn from_predictions(predictions: &Predictions, first_tree: usize) -> Result<Self> {
+        if predictions.predict_type() != PredictType::LeafIndex {
+            return Err(lgbm::Error::from_message(
+                "leaf indices need `PredictType::LeafIndex` predictions",
+            ));
+        }
+        Ok(Self {
+            num_rows: predictions.num_rows(),
+            first_tree,
+            num_trees: predictions.num_cols(),
+            // LightGBM returns the indices as doubles.
+            leaves: predictions.values().iter().map(|&v| v as u32).collect(),
+        })
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_trees(&self) -> usize {
+        self.num_trees
+    }
+
+    /// Index in the model of the tree in column 0.
+    pub fn first_tree(&self) -> usize {
+        self.first_tree
+    }
+
+    /// All leaf indices, row by row.
+    pub fn values(&self) -> &[u32] {
+        &self.leaves
+    }
+
+    pub fn row(&self, row: usize) -> &[u32] {
+        &self.leaves[row * self.num_trees..(row + 1) * self.num_trees]
+    }
+
+    /// One-hot encodes the leaves: one column per leaf of every predicted
+    /// tree, so each row has exactly one non-zero per tree.
+    ///
+    /// `num_leaves` holds the leaf count of every tree of the model, as
+    /// returned by [`tree_num_leaves`]. Columns only depend on the model, so
+    /// encodings of different datasets line up.
+    pub fn one_hot(&self, num_leaves: &[usize]) -> Result<CsrMatrix> {
+        let Some(trees) = num_leaves.get(self.first_tree..self.first_tree + self.num_trees) else {
+            return Err(lgbm::Error::from_message(&format!(
+                "leaf counts of {} trees, but trees {}..{} were predicted",
+                num_leaves.len(),
+                self.first_tree,
+                self.first_tree + self.num_trees
+            )));
+        };
+        let mut offsets = Vec::with_capacity(trees.len());
+        let mut num_cols = 0;
+        for &n in trees {
+            offsets.push(num_cols);
+            num_cols += n;
+        }
+
+        let mut indptr = Vec::with_capacity(self.num_rows + 1);
+        let mut indices = Vec::with_capacity(self.leaves.len());
+        indptr.push(0);
+        for row in 0..self.num_rows {
+            for (tree, &leaf) in self.row(row).iter().enumerate() {
+                let leaf = leaf as usize;
+                if leaf >= trees[tree] {
+                    return Err(lgbm::Error::from_message(&format!(
+                        "leaf {leaf} of tree {} which has {} leaves",
+                        self.first_tree + tree,
+                        trees[tree]
+                    )));
+                }
+                indices.push(offsets[tree] + leaf);
+            }
+            indptr.push(indices.len());
+        }
+        Ok(CsrMatrix {
+            num_rows: self.num_rows,
+            num_cols,
+            data: vec![1.0; indices.len()],
+            indptr,
+            indices,
+        })
+    }
+}
+
+/// Number of leaves of every tree in `booster`, in model order.
+pub fn tree_num_leaves(booster: &Booster) -> Result<Vec<usize>> {
+    let model = booster
+        .save_model_to_string(0, None, FeatureImportanceType::Split)?
+        .into_string()
+        .map_err(lgbm::Error::from_error)?;
+    let mut num_leaves = Vec::new();
+    let mut in_tree = false;
+    for line in model.lines() {
+        if line.starts_with("Tree=") {
+            in_tree = true;
+        } else if line == "end of trees" {
+            break;
+        } else if in_tree && let Some(n) = line.strip_prefix("num_leaves=") {
+            num_leaves.push(n.parse().map_err(lgbm::Error::from_error)?);
+            in_tree = false;
+        }
+    }
+    Ok(num_leaves)
+}
+
+/// Sparse matrix in compressed sparse row format, as used by scipy and most
+/// linear model libraries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsrMatrix {
+    pub num_rows: usize,
+    pub num_cols: usize,
+    /// Row `i` holds entries `indptr[i]..indptr[i + 1]`.
+    pub indptr: Vec<usize>,
+    /// Column of every entry.
+    pub indices: Vec<usize>,
+    pub data: Vec<f64>,
+}
+
+impl CsrMatrix {
+    /// `(column, value)` entries of `row`.
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.indptr[row]..self.indptr[row + 1];
+        self.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.data[range].iter().copied())
+    }
+
+    /// Writes `<index>:<value> ...` lines with zero-based column indices,
+    /// each prefixed with the row's label if `labels` is given. Fails with
+    /// [`io::ErrorKind::InvalidInput`] unless there is one label per row.
+    pub fn write_libsvm(&self, mut w: impl io::Write, labels: Option<&[f32]>) -> io::Result<()> {
+        if let Some(labels) = labels
+            && labels.len() != self.num_rows
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} labels for {} rows", labels.len(), self.num_rows),
+            ));
+        }
+        for row in 0..self.num_rows {
+            let mut first = true;
+            if let Some(labels) = labels {
+                write!(w, "{}", labels[row])?;
+                first = false;
+            }
+            for (col, value) in self.row(row) {
+                if !first {
+                    write!(w, " ")?;
+                }
+                write!(w, "{col}:{value}")?;
+                first = false;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two rows through trees 1 and 2 of a model.
+    fn leaves() -> LeafIndices {
+        LeafIndices {
+            num_rows: 2,
+            first_tree: 1,
+            num_trees: 2,
+            leaves: vec![0, 2, 1, 0],
+        }
+    }
+
+    #[test]
+    fn one_hot_offsets_leaves_by_tree() {
+        let csr = leaves().one_hot(&[4, 2, 3]).unwrap();
+        assert_eq!(
+            csr,
+            CsrMatrix {
+                num_rows: 2,
+                num_cols: 5,
+                indptr: vec![0, 2, 4],
+                indices: vec![0, 4, 1, 2],
+                data: vec![1.0; 4],
+            }
+        );
+        assert_eq!(csr.row(1).collect::<Vec<_>>(), [(1, 1.0), (2, 1.0)]);
+    }
+
+    #[test]
+    fn one_hot_checks_leaf_counts() {
+        // Too few trees for the predicted ones.
+        assert!(leaves().one_hot(&[4, 2]).is_err());
+        // Leaf 2 of a tree with two leaves.
+        assert!(leaves().one_hot(&[4, 2, 2]).is_err());
+    }
+
+    #[test]
+    fn write_libsvm_with_and_without_labels() {
+        let csr = leaves().one_hot(&[4, 2, 3]).unwrap();
+        let mut out = Vec::new();
+        csr.write_libsvm(&mut out, Some(&[1.0, 0.5])).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "1 0:1 4:1\n0.5 1:1 2:1\n");
+
+        let mut out = Vec::new();
+        csr.write_libsvm(&mut out, None).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0:1 4:1\n1:1 2:1\n");
+    }
+
+    #[test]
+    fn write_libsvm_rejects_label_mismatch() {
+        let csr = leaves().one_hot(&[4, 2, 3]).unwrap();
+        let mut out = Vec::new();
+        let err = csr.write_libsvm(&mut out, Some(&[1.0])).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn write_libsvm_of_empty_row() {
+        let csr = CsrMatrix {
+            num_rows: 1,
+            num_cols: 3,
+            indptr: vec![0, 0],
+            indices: Vec::new(),
+            data: Vec::new(),
+        };
+        let mut out = Vec::new();
+        csr.write_libsvm(&mut out, Some(&[2.0])).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "2\n");
+    }
+}
@@ -0,0 +1,39 @@
+//! Rust tooling for the statically built LightGBM in this repository, layered
+//! on top of [`lgbm`].
+
+mod build_info;
+mod callback;
+mod checkpoint;
+mod config;
+mod cv;
+mod early_stopping;
+mod explain;
+mod fast_predict;
+mod ffi;
+mod leaves;
+mod metadata;
+mod metric;
+mod objective;
+mod params;
+mod predictions;
+mod ranking;
+mod search;
+mod train;
+
+pub use build_info::*;
+pub use callback::*;
+pub use checkpoint::*;
+pub use config::*;
+pub use cv::*;
+pub use early_stopping::*;
+pub use explain::*;
+pub use fast_predict::*;
+pub use leaves::*;
+pub use metadata::*;
+pub use metric::*;
+pub use objective::*;
+pub use params::*;
+pub use predictions::*;
+pub use ranking::*;
+pub use search::*;
+pub use train::*;
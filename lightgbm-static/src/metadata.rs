@@ -0,0 +1,314 @@
+use crate::ffi::RawDataset;
+use lgbm::{Dataset, Field, Result};
+use std::{collections::HashSet, hash::Hash};
+
+/// Labels, weights, init scores, query groups and feature names of a
+/// [`Dataset`], checked against its number of rows before they reach
+/// LightGBM.
+///
+/// LightGBM does not report which of these fields are set, and reading an
+/// unset one through [`Dataset::get_field`] is unsound, so they are kept
+/// here and applied with [`Metadata::apply`]. A [`Trainer`](crate::Trainer)
+/// builds its datasets itself and applies them on its own.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    num_data: usize,
+    labels: Option<Vec<f32>>,
+    weights: Option<Vec<f32>>,
+    init_score: Option<Vec<f64>>,
+    groups: Option<Vec<i32>>,
+    feature_names: Option<Vec<String>>,
+}
+
+impl Metadata {
+    pub fn new(num_data: usize) -> Self {
+        Self {
+            num_data,
+            ..Self::default()
+        }
+    }
+
+    pub fn num_data(&self) -> usize {
+        self.num_data
+    }
+
+    pub fn set_labels(&mut self, labels: Vec<f32>) -> Result<()> {
+        self.check_len("labels", labels.len())?;
+        self.labels = Some(labels);
+        Ok(())
+    }
+
+    pub fn labels(&self) -> Option<&[f32]> {
+        self.labels.as_deref()
+    }
+
+    pub fn set_weights(&mut self, weights: Vec<f32>) -> Result<()> {
+        self.check_len("weights", weights.len())?;
+        if let Some(i) = weights.iter().position(|w| !w.is_finite() || *w < 0.0) {
+            return Err(lgbm::Error::from_message(&format!(
+                "invalid weight {} in row {i}",
+                weights[i]
+            )));
+        }
+        self.weights = Some(weights);
+        Ok(())
+    }
+
+    pub fn weights(&self) -> Option<&[f32]> {
+        self.weights.as_deref()
+    }
+
+    /// Sets init scores in LightGBM's layout: all rows of the first class,
+    /// then all rows of the next. The number of classes is inferred from the
+    /// length.
+    pub fn set_init_score(&mut self, scores: Vec<f64>) -> Result<()> {
+        if self.num_data == 0 || scores.is_empty() || !scores.len().is_multiple_of(self.num_data) {
+            return Err(lgbm::Error::from_message(&format!(
+                "{} init scores for {} rows; expected a multiple of the rows",
+                scores.len(),
+                self.num_data
+            )));
+        }
+        self.init_score = Some(scores);
+        Ok(())
+    }
+
+    /// Sets init scores given row by row, `num_class` per row, as returned by
+    /// predictions.
+    pub fn set_init_score_by_row(&mut self, scores: &[f64], num_class: usize) -> Result<()> {
+        if num_class == 0 || scores.len() != self.num_data * num_class {
+            return Err(lgbm::Error::from_message(&format!(
+                "{} init scores for {} rows of {num_class} classes",
+                scores.len(),
+                self.num_data
+            )));
+        }
+        let class_major = (0..num_class)
+            .flat_map(|class| (0..self.num_data).map(move |row| scores[row * num_class + class]))
+            .collect();
+        self.set_init_score(class_major)
+    }
+
+    /// Init scores in LightGBM's class-major layout.
+    pub fn init_score(&self) -> Option<&[f64]> {
+        self.init_score.as_deref()
+    }
+
+    /// Number of classes the init scores are for.
+    pub fn init_score_num_class(&self) -> Option<usize> {
+        self.init_score.as_ref().map(|s| s.len() / self.num_data)
+    }
+
+    /// Sets the sizes of consecutive query groups; they must cover all rows.
+    pub fn set_groups(&mut self, sizes: Vec<i32>) -> Result<()> {
+        if let Some(i) = sizes.iter().position(|&s| s <= 0) {
+            return Err(lgbm::Error::from_message(&format!(
+                "group {i} has size {}",
+                sizes[i]
+            )));
+        }
+        let total = sizes.iter().map(|&s| s as usize).sum::<usize>();
+        if total != self.num_data {
+            return Err(lgbm::Error::from_message(&format!(
+                "groups cover {total} rows, but there are {}",
+                self.num_data
+            )));
+        }
+        self.groups = Some(sizes);
+        Ok(())
+    }
+
+    /// Sets groups from a query id per row; see [`group_sizes`].
+    pub fn set_query_ids<Q: Hash + Eq>(&mut self, query_ids: &[Q]) -> Result<()> {
+        self.check_len("query ids", query_ids.len())?;
+        self.set_groups(group_sizes(query_ids)?)
+    }
+
+    /// Sizes of the query groups.
+    pub fn groups(&self) -> Option<&[i32]> {
+        self.groups.as_deref()
+    }
+
+    /// First row of every group, followed by the number of rows.
+    pub fn group_boundaries(&self) -> Option<Vec<usize>> {
+        let sizes = self.groups.as_ref()?;
+        let mut boundaries = Vec::with_capacity(sizes.len() + 1);
+        boundaries.push(0);
+        for &size in sizes {
+            boundaries.push(boundaries.last().unwrap() + size as usize);
+        }
+        Some(boundaries)
+    }
+
+    /// Names of the feature columns, saved with trained models.
+    pub fn set_feature_names(&mut self, names: Vec<String>) {
+        self.feature_names = Some(names);
+    }
+
+    pub fn feature_names(&self) -> Option<&[String]> {
+        self.feature_names.as_deref()
+    }
+
+    /// Sets every field that is present on `dataset`, which must have
+    /// [`Metadata::num_data`] rows.
+    pub fn apply(&self, dataset: &mut Dataset) -> Result<()> {
+        self.check_num_data(dataset.get_num_data()?)?;
+        if let Some(labels) = &self.labels {
+            dataset.set_field(Field::LABEL, labels)?;
+        }
+        if let Some(weights) = &self.weights {
+            dataset.set_field(Field::WEIGHT, weights)?;
+        }
+        if let Some(init_score) = &self.init_score {
+            dataset.set_field(Field::INIT_SCORE, init_score)?;
+        }
+        if let Some(groups) = &self.groups {
+            dataset.set_field(Field::GROUP, groups)?;
+        }
+        if let Some(names) = &self.feature_names {
+            dataset.set_feature_names(names)?;
+        }
+        Ok(())
+    }
+
+    /// [`Metadata::apply`] for datasets built by the [`Trainer`](crate::Trainer).
+    pub(crate) fn apply_raw(&self, dataset: &mut RawDataset) -> Result<()> {
+        self.check_num_data(dataset.num_data()?)?;
+        if let Some(labels) = &self.labels {
+            dataset.set_field(c"label", labels)?;
+        }
+        if let Some(weights) = &self.weights {
+            dataset.set_field(c"weight", weights)?;
+        }
+        if let Some(init_score) = &self.init_score {
+            dataset.set_field(c"init_score", init_score)?;
+        }
+        if let Some(groups) = &self.groups {
+            dataset.set_field(c"group", groups)?;
+        }
+        if let Some(names) = &self.feature_names {
+            dataset.set_feature_names(names)?;
+        }
+        Ok(())
+    }
+
+    fn check_num_data(&self, num_data: usize) -> Result<()> {
+        if num_data != self.num_data {
+            return Err(lgbm::Error::from_message(&format!(
+                "metadata for {} rows applied to a dataset of {num_data}",
+                self.num_data
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_len(&self, what: &str, len: usize) -> Result<()> {
+        if len != self.num_data {
+            return Err(lgbm::Error::from_message(&format!(
+                "{len} {what} for {} rows",
+                self.num_data
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Group sizes from a query id per row. Rows of a query must be
+/// consecutive, as in data sorted by query id.
+pub fn group_sizes<Q: Hash + Eq>(query_ids: &[Q]) -> Result<Vec<i32>> {
+    let mut sizes = Vec::new();
+    let mut seen = HashSet::new();
+    let mut row = 0;
+    for run in query_ids.chunk_by(|a, b| a == b) {
+        if !seen.insert(&run[0]) {
+            return Err(lgbm::Error::from_message(&format!(
+                "query id of row {row} appeared before; sort the data by query id"
+            )));
+        }
+        row += run.len();
+        let size = i32::try_from(run.len())
+            .map_err(|_| lgbm::Error::from_message("query group too large"))?;
+        sizes.push(size);
+    }
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_sizes_counts_consecutive_runs() {
+        assert_eq!(group_sizes(&[7, 7, 7, 3, 9, 9]).unwrap(), [3, 1, 2]);
+        assert_eq!(group_sizes(&["q1"]).unwrap(), [1]);
+        assert_eq!(group_sizes::<u32>(&[]).unwrap(), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn group_sizes_rejects_unsorted_queries() {
+        assert!(group_sizes(&[1, 1, 2, 1]).is_err());
+    }
+
+    #[test]
+    fn lengths_must_match_rows() {
+        let mut metadata = Metadata::new(3);
+        assert!(metadata.set_labels(vec![0.0; 2]).is_err());
+        assert!(metadata.set_weights(vec![1.0; 4]).is_err());
+        assert!(metadata.set_query_ids(&[1, 1]).is_err());
+        assert_eq!(metadata, Metadata::new(3));
+
+        metadata.set_labels(vec![0.0, 1.0, 0.0]).unwrap();
+        metadata.set_weights(vec![1.0, 2.0, 0.0]).unwrap();
+        assert_eq!(metadata.labels(), Some(&[0.0, 1.0, 0.0][..]));
+        assert_eq!(metadata.weights(), Some(&[1.0, 2.0, 0.0][..]));
+    }
+
+    #[test]
+    fn weights_must_be_finite_and_non_negative() {
+        let mut metadata = Metadata::new(2);
+        assert!(metadata.set_weights(vec![1.0, -1.0]).is_err());
+        assert!(metadata.set_weights(vec![f32::NAN, 1.0]).is_err());
+        assert!(metadata.set_weights(vec![f32::INFINITY, 1.0]).is_err());
+        assert_eq!(metadata.weights(), None);
+    }
+
+    #[test]
+    fn init_score_is_a_multiple_of_rows() {
+        let mut metadata = Metadata::new(2);
+        assert!(metadata.set_init_score(vec![]).is_err());
+        assert!(metadata.set_init_score(vec![0.0; 3]).is_err());
+        metadata.set_init_score(vec![0.0; 6]).unwrap();
+        assert_eq!(metadata.init_score_num_class(), Some(3));
+
+        assert!(Metadata::new(0).set_init_score(vec![0.0]).is_err());
+    }
+
+    #[test]
+    fn init_score_by_row_is_transposed() {
+        let mut metadata = Metadata::new(2);
+        assert!(metadata.set_init_score_by_row(&[1.0; 4], 3).is_err());
+        assert!(metadata.set_init_score_by_row(&[], 0).is_err());
+        // Rows (1, 2, 3) and (4, 5, 6) of three classes.
+        metadata
+            .set_init_score_by_row(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3)
+            .unwrap();
+        assert_eq!(
+            metadata.init_score(),
+            Some(&[1.0, 4.0, 2.0, 5.0, 3.0, 6.0][..])
+        );
+    }
+
+    #[test]
+    fn groups_must_cover_all_rows() {
+        let mut metadata = Metadata::new(5);
+        assert!(metadata.set_groups(vec![2, 2]).is_err());
+        assert!(metadata.set_groups(vec![2, 4]).is_err());
+        assert!(metadata.set_groups(vec![5, 0]).is_err());
+        assert!(metadata.set_groups(vec![6, -1]).is_err());
+        assert_eq!(metadata.group_boundaries(), None);
+
+        metadata.set_query_ids(&["a", "a", "b", "c", "c"]).unwrap();
+        assert_eq!(metadata.groups(), Some(&[2, 1, 2][..]));
+        assert_eq!(metadata.group_boundaries(), Some(vec![0, 2, 3, 5]));
+    }
+}
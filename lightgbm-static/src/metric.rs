@@ -0,0 +1,49 @@
+/// Evaluation metric implemented in Rust, computed by the [`Trainer`](crate::Trainer)
+/// after every iteration next to LightGBM's own metrics.
+///
+/// `preds` are the booster's current predictions for a dataset: probabilities
+/// for `binary` and `multiclass`, raw scores for `objective=custom`. For
+/// multiclass models they hold all rows of the first class, then all rows of
+/// the next. `weights` are those of the dataset's [`Metadata`](crate::Metadata).
+///
+/// ```no_run
+/// # use lightgbm_static::Metric;
+/// /// Misclassification cost where false negatives cost `fn_cost` each.
+/// struct CostWeightedError {
+///     fn_cost: f64,
+/// }
+///
+/// impl Metric for CostWeightedError {
+///     fn name(&self) -> &str {
+///         "cost_weighted_error"
+///     }
+///
+///     fn higher_is_better(&self) -> bool {
+///         false
+///     }
+///
+///     fn evaluate(&mut self, preds: &[f64], labels: &[f32], weights: Option<&[f32]>) -> f64 {
+///         let (mut cost, mut total) = (0.0, 0.0);
+///         for (i, (&pred, &label)) in preds.iter().zip(labels).enumerate() {
+///             let weight = weights.map_or(1.0, |w| f64::from(w[i]));
+///             cost += weight * match (pred > 0.5, label > 0.5) {
+///                 (false, true) => self.fn_cost,
+///                 (true, false) => 1.0,
+///                 _ => 0.0,
+///             };
+///             total += weight;
+///         }
+///         cost / total
+///     }
+/// }
+/// ```
+pub trait Metric {
+    /// Name the values are recorded under; must not clash with LightGBM's
+    /// metrics.
+    fn name(&self) -> &str;
+
+    /// Direction used by [`EarlyStopping`](crate::EarlyStopping).
+    fn higher_is_better(&self) -> bool;
+
+    fn evaluate(&mut self, preds: &[f64], labels: &[f32], weights: Option<&[f32]>) -> f64;
+}
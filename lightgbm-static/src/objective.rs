@@ -0,0 +1,60 @@
+use lgbm::Result;
+
+/// Loss implemented in Rust, boosted with `LGBM_BoosterUpdateOneIterCustom`.
+///
+/// `scores` are the current raw scores of the training data and `labels` its
+/// labels. For multiclass models scores, gradients and hessians hold all rows
+/// of the first class, then all rows of the next. The booster must be created
+/// with `objective=custom` so LightGBM applies no transform of its own;
+/// [`Trainer::set_objective`](crate::Trainer::set_objective) checks this.
+///
+/// Closures with the same signature as [`Objective::gradients`] implement the
+/// trait:
+///
+/// ```no_run
+/// # use lightgbm_static::Trainer;
+/// # fn f(trainer: &mut Trainer) -> lgbm::Result<()> {
+/// // Squared error that penalises under-prediction four times as much.
+/// trainer.set_objective(|scores: &[f64], labels: &[f32]| {
+///     scores
+///         .iter()
+///         .zip(labels)
+///         .map(|(&score, &label)| {
+///             let residual = score - f64::from(label);
+///             let weight = if residual < 0.0 { 4.0 } else { 1.0 };
+///             ((weight * residual) as f32, weight as f32)
+///         })
+///         .unzip()
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Objective {
+    /// Gradients and hessians of the loss with respect to `scores`.
+    fn gradients(&mut self, scores: &[f64], labels: &[f32]) -> (Vec<f32>, Vec<f32>);
+}
+
+impl<F: FnMut(&[f64], &[f32]) -> (Vec<f32>, Vec<f32>)> Objective for F {
+    fn gradients(&mut self, scores: &[f64], labels: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        self(scores, labels)
+    }
+}
+
+/// Checks that an [`Objective`] returned one finite value per score.
+pub(crate) fn check_gradients(num_scores: usize, grad: &[f32], hess: &[f32]) -> Result<()> {
+    for (name, values) in [("gradients", grad), ("hessians", hess)] {
+        if values.len() != num_scores {
+            return Err(lgbm::Error::from_message(&format!(
+                "custom objective returned {} {name} for {num_scores} scores",
+                values.len()
+            )));
+        }
+        if let Some(i) = values.iter().position(|v| !v.is_finite()) {
+            return Err(lgbm::Error::from_message(&format!(
+                "custom objective returned {} for {name}[{i}]",
+                values[i]
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,1095 @@
+use lgbm::{
+    Parameters,
+    parameters::{Metric, Objective, Verbosity},
+};
+use std::str::FromStr;
+
+/// Invalid parameter, reported before anything reaches LightGBM.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamError {
+    /// Canonical LightGBM name of the offending parameter.
+    pub key: String,
+    pub message: String,
+}
+
+impl ParamError {
+    pub(crate) fn new(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`: {}", self.key, self.message)
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl From<ParamError> for lgbm::Error {
+    fn from(e: ParamError) -> Self {
+        lgbm::Error::from_message(&e.to_string())
+    }
+}
+
+/// Typed LightGBM parameters.
+///
+/// Unset fields are left out of the rendered string so LightGBM applies its
+/// own defaults. Parameters without a typed field are kept in `extra` and
+/// passed through verbatim.
+///
+/// ```
+/// use lgbm::parameters::{Metric, Objective};
+/// use lightgbm_static::Params;
+///
+/// let params = Params::builder()
+///     .objective(Objective::Regression)
+///     .metric(Metric::L2)
+///     .num_leaves(10)
+///     .learning_rate(0.05)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     params.to_string(),
+///     "objective=regression metric=l2 learning_rate=0.05 num_leaves=10"
+/// );
+/// assert_eq!(params.to_string().parse::<Params>().unwrap(), params);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params {
+    pub objective: Option<Objective>,
+    /// `Some(vec![])` disables evaluation (`metric=None`).
+    pub metrics: Option<Vec<Metric>>,
+    pub num_class: Option<usize>,
+    pub num_iterations: Option<usize>,
+    pub learning_rate: Option<f64>,
+    pub num_leaves: Option<usize>,
+    /// `<= 0` means no limit.
+    pub max_depth: Option<i32>,
+    pub min_data_in_leaf: Option<usize>,
+    pub min_sum_hessian_in_leaf: Option<f64>,
+    pub bagging_fraction: Option<f64>,
+    pub bagging_freq: Option<usize>,
+    pub feature_fraction: Option<f64>,
+    pub lambda_l1: Option<f64>,
+    pub lambda_l2: Option<f64>,
+    /// `0` lets OpenMP pick.
+    pub num_threads: Option<usize>,
+    pub seed: Option<i64>,
+    pub verbosity: Option<Verbosity>,
+    /// Other parameters as `(key, value)`, in order. Keys are kept as given,
+    /// so aliases are passed through verbatim.
+    pub extra: Vec<(String, String)>,
+}
+
+/// LightGBM parameter names with typed fields, and their aliases.
+///
+/// <https://lightgbm.readthedocs.io/en/latest/Parameters.html>
+const ALIASES: &[(&str, &[&str])] = &[
+    (
+        "objective",
+        &["objective_type", "app", "application", "loss"],
+    ),
+    ("metric", &["metrics", "metric_types"]),
+    ("num_class", &["num_classes"]),
+    (
+        "num_iterations",
+        &[
+            "num_iteration",
+            "n_iter",
+            "num_tree",
+            "num_trees",
+            "num_round",
+            "num_rounds",
+            "nrounds",
+            "num_boost_round",
+            "n_estimators",
+            "max_iter",
+        ],
+    ),
+    ("learning_rate", &["shrinkage_rate", "eta"]),
+    (
+        "num_leaves",
+        &["num_leaf", "max_leaves", "max_leaf", "max_leaf_nodes"],
+    ),
+    ("max_depth", &[]),
+    (
+        "min_data_in_leaf",
+        &[
+            "min_data_per_leaf",
+            "min_data",
+            "min_child_samples",
+            "min_samples_leaf",
+        ],
+    ),
+    (
+        "min_sum_hessian_in_leaf",
+        &[
+            "min_sum_hessian_per_leaf",
+            "min_sum_hessian",
+            "min_hessian",
+            "min_child_weight",
+        ],
+    ),
+    ("bagging_fraction", &["sub_row", "subsample", "bagging"]),
+    ("bagging_freq", &["subsample_freq"]),
+    ("feature_fraction", &["sub_feature", "colsample_bytree"]),
+    ("lambda_l1", &["reg_alpha", "l1_regularization"]),
+    ("lambda_l2", &["reg_lambda", "lambda", "l2_regularization"]),
+    (
+        "num_threads",
+        &["num_thread", "nthread", "nthreads", "n_jobs"],
+    ),
+    ("seed", &["random_seed", "random_state"]),
+    ("verbosity", &["verbose"]),
+];
+
+/// LightGBM parameters without a typed field that are used for training,
+/// and their aliases.
+const OTHER_PARAMETERS: &[(&str, &[&str])] = &[
+    // Core
+    ("config", &["config_file"]),
+    ("task", &["task_type"]),
+    ("boosting", &["boosting_type", "boost"]),
+    ("data_sample_strategy", &[]),
+    ("tree_learner", &["tree", "tree_type", "tree_learner_type"]),
+    ("device_type", &["device"]),
+    ("deterministic", &[]),
+    // Learning control
+    ("force_col_wise", &[]),
+    ("force_row_wise", &[]),
+    ("histogram_pool_size", &["hist_pool_size"]),
+    ("bagging_seed", &["bagging_fraction_seed"]),
+    (
+        "pos_bagging_fraction",
+        &["pos_sub_row", "pos_subsample", "pos_bagging"],
+    ),
+    (
+        "neg_bagging_fraction",
+        &["neg_sub_row", "neg_subsample", "neg_bagging"],
+    ),
+    ("bagging_by_query", &[]),
+    (
+        "feature_fraction_bynode",
+        &["sub_feature_bynode", "colsample_bynode"],
+    ),
+    ("feature_fraction_seed", &[]),
+    ("extra_trees", &["extra_tree"]),
+    ("extra_seed", &[]),
+    (
+        "early_stopping_round",
+        &[
+            "early_stopping_rounds",
+            "early_stopping",
+            "n_iter_no_change",
+        ],
+    ),
+    ("early_stopping_min_delta", &[]),
+    ("first_metric_only", &[]),
+    ("max_delta_step", &["max_tree_output", "max_leaf_output"]),
+    ("linear_lambda", &[]),
+    ("min_gain_to_split", &["min_split_gain"]),
+    ("drop_rate", &["rate_drop"]),
+    ("max_drop", &[]),
+    ("skip_drop", &[]),
+    ("xgboost_dart_mode", &[]),
+    ("uniform_drop", &[]),
+    ("drop_seed", &[]),
+    ("top_rate", &[]),
+    ("other_rate", &[]),
+    ("min_data_per_group", &[]),
+    ("max_cat_threshold", &[]),
+    ("cat_l2", &[]),
+    ("cat_smooth", &[]),
+    ("max_cat_to_onehot", &[]),
+    ("top_k", &["topk"]),
+    (
+        "monotone_constraints",
+        &["mc", "monotone_constraint", "monotonic_cst"],
+    ),
+    (
+        "monotone_constraints_method",
+        &["monotone_constraining_method", "mc_method"],
+    ),
+    (
+        "monotone_penalty",
+        &["monotone_splits_penalty", "ms_penalty", "mc_penalty"],
+    ),
+    (
+        "feature_contri",
+        &["feature_contrib", "fc", "fp", "feature_penalty"],
+    ),
+    (
+        "forcedsplits_filename",
+        &[
+            "fs",
+            "forced_splits_filename",
+            "forced_splits_file",
+            "forced_splits",
+        ],
+    ),
+    ("refit_decay_rate", &[]),
+    ("cegb_tradeoff", &[]),
+    ("cegb_penalty_split", &[]),
+    ("cegb_penalty_feature_lazy", &[]),
+    ("cegb_penalty_feature_coupled", &[]),
+    ("path_smooth", &[]),
+    ("interaction_constraints", &[]),
+    ("tree_interaction_constraints", &[]),
+    ("use_quantized_grad", &[]),
+    ("num_grad_quant_bins", &[]),
+    ("quant_train_renew_leaf", &[]),
+    ("stochastic_rounding", &[]),
+    ("input_model", &["model_input", "model_in"]),
+    ("output_model", &["model_output", "model_out"]),
+    ("saved_feature_importance_type", &[]),
+    ("snapshot_freq", &["save_period"]),
+    // Objective
+    ("objective_seed", &[]),
+    ("is_unbalance", &["unbalance", "unbalanced_sets"]),
+    ("scale_pos_weight", &[]),
+    ("sigmoid", &[]),
+    ("boost_from_average", &[]),
+    ("reg_sqrt", &[]),
+    ("alpha", &[]),
+    ("fair_c", &[]),
+    ("poisson_max_delta_step", &[]),
+    ("tweedie_variance_power", &[]),
+    ("lambdarank_truncation_level", &[]),
+    ("lambdarank_norm", &[]),
+    ("label_gain", &[]),
+    ("lambdarank_position_bias_regularization", &[]),
+    // Metric
+    ("metric_freq", &["output_freq"]),
+    (
+        "is_provide_training_metric",
+        &["training_metric", "is_training_metric", "train_metric"],
+    ),
+    (
+        "eval_at",
+        &["ndcg_eval_at", "ndcg_at", "map_eval_at", "map_at"],
+    ),
+    ("multi_error_top_k", &[]),
+    ("auc_mu_weights", &[]),
+    // Network and GPU
+    ("num_machines", &["num_machine"]),
+    ("local_listen_port", &["local_port", "port"]),
+    ("time_out", &[]),
+    (
+        "machine_list_filename",
+        &["machine_list_file", "machine_list", "mlist"],
+    ),
+    ("machines", &["workers", "nodes"]),
+    ("gpu_platform_id", &[]),
+    ("gpu_device_id", &[]),
+    ("gpu_use_dp", &[]),
+    ("num_gpu", &[]),
+];
+
+/// LightGBM dataset parameters and their aliases.
+const DATASET_PARAMETERS: &[(&str, &[&str])] = &[
+    ("linear_tree", &["linear_trees"]),
+    ("max_bin", &["max_bins"]),
+    ("max_bin_by_feature", &[]),
+    ("min_data_in_bin", &[]),
+    ("bin_construct_sample_cnt", &["subsample_for_bin"]),
+    ("data_random_seed", &["data_seed"]),
+    (
+        "is_enable_sparse",
+        &["is_sparse", "enable_sparse", "sparse"],
+    ),
+    ("enable_bundle", &["is_enable_bundle", "bundle"]),
+    ("use_missing", &[]),
+    ("zero_as_missing", &[]),
+    ("feature_pre_filter", &[]),
+    ("pre_partition", &["is_pre_partition"]),
+    ("two_round", &["two_round_loading", "use_two_round_loading"]),
+    ("header", &["has_header"]),
+    ("label_column", &["label"]),
+    ("weight_column", &["weight"]),
+    (
+        "group_column",
+        &["group", "group_id", "query_column", "query", "query_id"],
+    ),
+    ("ignore_column", &["ignore_feature", "blacklist"]),
+    (
+        "categorical_feature",
+        &[
+            "cat_feature",
+            "categorical_column",
+            "cat_column",
+            "categorical_features",
+        ],
+    ),
+    ("forcedbins_filename", &[]),
+    ("save_binary", &["is_save_binary", "is_save_binary_file"]),
+    ("precise_float_parser", &[]),
+    ("parser_config_file", &[]),
+];
+
+/// LightGBM prediction parameters and their aliases.
+const PREDICTION_PARAMETERS: &[(&str, &[&str])] = &[
+    ("start_iteration_predict", &[]),
+    ("num_iteration_predict", &[]),
+    (
+        "predict_raw_score",
+        &["is_predict_raw_score", "predict_rawscore", "raw_score"],
+    ),
+    (
+        "predict_leaf_index",
+        &["is_predict_leaf_index", "leaf_index"],
+    ),
+    ("predict_contrib", &["is_predict_contrib", "contrib"]),
+    ("predict_disable_shape_check", &[]),
+    ("pred_early_stop", &[]),
+    ("pred_early_stop_freq", &[]),
+    ("pred_early_stop_margin", &[]),
+    (
+        "output_result",
+        &[
+            "predict_result",
+            "prediction_result",
+            "predict_name",
+            "prediction_name",
+            "pred_name",
+            "name_pred",
+        ],
+    ),
+];
+
+/// Typed parameters that apply to datasets, training and prediction alike.
+const GENERAL_PARAMETERS: &[&str] = &["num_threads", "seed", "verbosity"];
+
+/// Where a LightGBM parameter is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ParameterKind {
+    /// Applies everywhere, e.g. `num_threads`.
+    General,
+    Dataset,
+    Training,
+    Prediction,
+}
+
+/// Whether `key` is a LightGBM parameter name or alias.
+pub fn is_known_parameter(key: &str) -> bool {
+    parameter_kind(key).is_some()
+}
+
+/// Where the parameter `key`, a name or alias, is used; `None` if unknown.
+pub(crate) fn parameter_kind(key: &str) -> Option<ParameterKind> {
+    if let Some(name) = canonical(key) {
+        return Some(if GENERAL_PARAMETERS.contains(&name) {
+            ParameterKind::General
+        } else {
+            ParameterKind::Training
+        });
+    }
+    [
+        (DATASET_PARAMETERS, ParameterKind::Dataset),
+        (OTHER_PARAMETERS, ParameterKind::Training),
+        (PREDICTION_PARAMETERS, ParameterKind::Prediction),
+    ]
+    .into_iter()
+    .find(|(list, _)| find_alias(list, key).is_some())
+    .map(|(_, kind)| kind)
+}
+
+/// Canonical name of `key` if it has a typed field.
+fn canonical(key: &str) -> Option<&'static str> {
+    find_alias(ALIASES, key)
+}
+
+/// Canonical name of `key` for any known parameter, or `key` itself.
+fn canonical_untyped(key: &str) -> &str {
+    [DATASET_PARAMETERS, OTHER_PARAMETERS, PREDICTION_PARAMETERS]
+        .into_iter()
+        .find_map(|list| find_alias(list, key))
+        .unwrap_or(key)
+}
+
+fn find_alias(list: &[(&'static str, &[&str])], key: &str) -> Option<&'static str> {
+    list.iter()
+        .find(|(name, aliases)| *name == key || aliases.contains(&key))
+        .map(|(name, _)| *name)
+}
+
+impl Params {
+    pub fn builder() -> ParamsBuilder {
+        ParamsBuilder::default()
+    }
+
+    /// Sets a parameter from its LightGBM name (or alias) and string value.
+    ///
+    /// Setting the same parameter twice, under any of its names, is an error:
+    /// LightGBM would silently keep the first value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        let key = key.trim();
+        let value = value.trim();
+        if key.is_empty() || key.contains(|c: char| c.is_whitespace() || c == '=') {
+            return Err(ParamError::new(key, "invalid parameter name"));
+        }
+        if value.contains(char::is_whitespace) {
+            return Err(ParamError::new(key, "value must not contain whitespace"));
+        }
+        let Some(name) = canonical(key) else {
+            let name = canonical_untyped(key);
+            if self.extra.iter().any(|(k, _)| canonical_untyped(k) == name) {
+                return Err(ParamError::new(name, "set more than once"));
+            }
+            self.extra.push((key.to_string(), value.to_string()));
+            return Ok(());
+        };
+        if self.contains(name) {
+            return Err(ParamError::new(name, "set more than once"));
+        }
+        match name {
+            "objective" => self.objective = Some(parse_objective(value)?),
+            "metric" => self.metrics = Some(parse_metrics(value)?),
+            "num_class" => self.num_class = Some(parse(name, value)?),
+            "num_iterations" => self.num_iterations = Some(parse(name, value)?),
+            "learning_rate" => self.learning_rate = Some(parse(name, value)?),
+            "num_leaves" => self.num_leaves = Some(parse(name, value)?),
+            "max_depth" => self.max_depth = Some(parse(name, value)?),
+            "min_data_in_leaf" => self.min_data_in_leaf = Some(parse(name, value)?),
+            "min_sum_hessian_in_leaf" => self.min_sum_hessian_in_leaf = Some(parse(name, value)?),
+            "bagging_fraction" => self.bagging_fraction = Some(parse(name, value)?),
+            "bagging_freq" => self.bagging_freq = Some(parse(name, value)?),
+            "feature_fraction" => self.feature_fraction = Some(parse(name, value)?),
+            "lambda_l1" => self.lambda_l1 = Some(parse(name, value)?),
+            "lambda_l2" => self.lambda_l2 = Some(parse(name, value)?),
+            "num_threads" => self.num_threads = Some(parse(name, value)?),
+            "seed" => self.seed = Some(parse(name, value)?),
+            "verbosity" => self.verbosity = Some(parse_verbosity(value)?),
+            _ => unreachable!("`{name}` is listed in ALIASES"),
+        }
+        Ok(())
+    }
+
+    /// Sets a parameter, replacing any earlier value of it or its aliases.
+    pub fn replace(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        self.remove(key);
+        self.set(key, value)
+    }
+
+    /// Unsets a parameter given by LightGBM name or alias.
+    pub fn remove(&mut self, key: &str) {
+        let key = key.trim();
+        match canonical(key) {
+            Some("objective") => self.objective = None,
+            Some("metric") => self.metrics = None,
+            Some("num_class") => self.num_class = None,
+            Some("num_iterations") => self.num_iterations = None,
+            Some("learning_rate") => self.learning_rate = None,
+            Some("num_leaves") => self.num_leaves = None,
+            Some("max_depth") => self.max_depth = None,
+            Some("min_data_in_leaf") => self.min_data_in_leaf = None,
+            Some("min_sum_hessian_in_leaf") => self.min_sum_hessian_in_leaf = None,
+            Some("bagging_fraction") => self.bagging_fraction = None,
+            Some("bagging_freq") => self.bagging_freq = None,
+            Some("feature_fraction") => self.feature_fraction = None,
+            Some("lambda_l1") => self.lambda_l1 = None,
+            Some("lambda_l2") => self.lambda_l2 = None,
+            Some("num_threads") => self.num_threads = None,
+            Some("seed") => self.seed = None,
+            Some("verbosity") => self.verbosity = None,
+            Some(name) => unreachable!("`{name}` is listed in ALIASES"),
+            None => {
+                let name = canonical_untyped(key);
+                self.extra.retain(|(k, _)| canonical_untyped(k) != name);
+            }
+        }
+    }
+
+    /// Adds every parameter set in `other`; setting one twice is an error.
+    pub fn extend(&mut self, other: &Params) -> Result<(), ParamError> {
+        for (key, value) in other.to_pairs() {
+            self.set(&key, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a parameter, given by LightGBM name or alias, is set.
+    pub fn contains(&self, key: &str) -> bool {
+        match canonical(key) {
+            Some(name) => self.to_pairs().iter().any(|(k, _)| k == name),
+            None => {
+                let name = canonical_untyped(key);
+                self.extra.iter().any(|(k, _)| canonical_untyped(k) == name)
+            }
+        }
+    }
+
+    /// Checks ranges and combinations LightGBM would reject or ignore.
+    pub fn validate(&self) -> Result<(), ParamError> {
+        fn check<T: Copy>(
+            key: &str,
+            value: Option<T>,
+            ok: impl Fn(T) -> bool,
+            expected: &str,
+        ) -> Result<(), ParamError> {
+            match value {
+                Some(v) if !ok(v) => Err(ParamError::new(key, format!("must be {expected}"))),
+                _ => Ok(()),
+            }
+        }
+        let finite_non_negative = |v: f64| v.is_finite() && v >= 0.0;
+        let fraction = |v: f64| v > 0.0 && v <= 1.0;
+
+        check(
+            "learning_rate",
+            self.learning_rate,
+            |v| v.is_finite() && v > 0.0,
+            "> 0",
+        )?;
+        check(
+            "num_leaves",
+            self.num_leaves,
+            |v| (2..=131_072).contains(&v),
+            "in 2..=131072",
+        )?;
+        check(
+            "min_sum_hessian_in_leaf",
+            self.min_sum_hessian_in_leaf,
+            finite_non_negative,
+            ">= 0",
+        )?;
+        check(
+            "bagging_fraction",
+            self.bagging_fraction,
+            fraction,
+            "in (0, 1]",
+        )?;
+        check(
+            "feature_fraction",
+            self.feature_fraction,
+            fraction,
+            "in (0, 1]",
+        )?;
+        check("lambda_l1", self.lambda_l1, finite_non_negative, ">= 0")?;
+        check("lambda_l2", self.lambda_l2, finite_non_negative, ">= 0")?;
+
+        if self.bagging_fraction.is_some_and(|v| v < 1.0) && self.bagging_freq.unwrap_or(0) == 0 {
+            return Err(ParamError::new(
+                "bagging_fraction",
+                "has no effect unless `bagging_freq` > 0",
+            ));
+        }
+
+        let objective = self.objective.unwrap_or_default();
+        let multiclass = matches!(objective, Objective::Multiclass | Objective::Multiclassova);
+        let ranking = self.is_ranking();
+        // A custom objective sets the number of scores per row through
+        // `num_class`, so any positive value is fine.
+        let custom = matches!(objective, Objective::Custom);
+        match (multiclass, self.num_class) {
+            (true, None) => {
+                return Err(ParamError::new(
+                    "num_class",
+                    format!("is required by `objective={objective}`"),
+                ));
+            }
+            (true, Some(n)) if n < 2 => {
+                return Err(ParamError::new(
+                    "num_class",
+                    format!("must be >= 2 for `objective={objective}`"),
+                ));
+            }
+            (false, Some(0)) if custom => {
+                return Err(ParamError::new(
+                    "num_class",
+                    format!("must be >= 1 for `objective={objective}`"),
+                ));
+            }
+            (false, Some(n)) if n != 1 && !custom => {
+                return Err(ParamError::new(
+                    "num_class",
+                    format!("must be 1 for `objective={objective}`"),
+                ));
+            }
+            _ => {}
+        }
+        for &metric in self.metrics.iter().flatten() {
+            let compatible = match metric {
+                Metric::MultiLogloss | Metric::MultiError | Metric::AucMu => {
+                    multiclass || (custom && self.num_class.unwrap_or(1) > 1)
+                }
+                Metric::Ndcg | Metric::Map => ranking || custom,
+                _ => !multiclass,
+            };
+            if !compatible {
+                return Err(ParamError::new(
+                    "metric",
+                    format!("`{metric}` cannot be used with `objective={objective}`"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the objective is a learning-to-rank one, which needs query
+    /// groups on every dataset.
+    pub fn is_ranking(&self) -> bool {
+        matches!(
+            self.objective,
+            Some(Objective::Lambdarank | Objective::RankXendcg)
+        )
+    }
+
+    /// Validates and converts to [`lgbm::Parameters`].
+    pub fn to_parameters(&self) -> Result<Parameters, ParamError> {
+        self.validate()?;
+        let mut p = Parameters::new();
+        for (key, value) in self.to_pairs() {
+            p.push(key, value);
+        }
+        Ok(p)
+    }
+
+    pub(crate) fn to_pairs(&self) -> Vec<(String, String)> {
+        fn push<T: ToString>(pairs: &mut Vec<(String, String)>, key: &str, value: Option<T>) {
+            if let Some(value) = value {
+                pairs.push((key.to_string(), value.to_string()));
+            }
+        }
+        let mut pairs = Vec::new();
+        push(&mut pairs, "objective", self.objective);
+        push(
+            &mut pairs,
+            "metric",
+            self.metrics.as_ref().map(|m| render_metrics(m)),
+        );
+        push(&mut pairs, "num_class", self.num_class);
+        push(&mut pairs, "num_iterations", self.num_iterations);
+        push(&mut pairs, "learning_rate", self.learning_rate);
+        push(&mut pairs, "num_leaves", self.num_leaves);
+        push(&mut pairs, "max_depth", self.max_depth);
+        push(&mut pairs, "min_data_in_leaf", self.min_data_in_leaf);
+        push(
+            &mut pairs,
+            "min_sum_hessian_in_leaf",
+            self.min_sum_hessian_in_leaf,
+        );
+        push(&mut pairs, "bagging_fraction", self.bagging_fraction);
+        push(&mut pairs, "bagging_freq", self.bagging_freq);
+        push(&mut pairs, "feature_fraction", self.feature_fraction);
+        push(&mut pairs, "lambda_l1", self.lambda_l1);
+        push(&mut pairs, "lambda_l2", self.lambda_l2);
+        push(&mut pairs, "num_threads", self.num_threads);
+        push(&mut pairs, "seed", self.seed);
+        push(
+            &mut pairs,
+            "verbosity",
+            self.verbosity.map(verbosity_to_int),
+        );
+        pairs.extend(self.extra.iter().cloned());
+        pairs
+    }
+}
+
+/// Renders the LightGBM parameter string, `key=value` separated by spaces.
+impl std::fmt::Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (key, value)) in self.to_pairs().iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses and validates a LightGBM parameter string. Aliases are accepted and
+/// stored under their canonical name.
+impl FromStr for Params {
+    type Err = ParamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = Params::default();
+        for param in s.split_whitespace() {
+            let Some((key, value)) = param.split_once('=') else {
+                return Err(ParamError::new(param, "expected `key=value`"));
+            };
+            params.set(key, value)?;
+        }
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+impl TryFrom<&Parameters> for Params {
+    type Error = ParamError;
+
+    fn try_from(p: &Parameters) -> Result<Self, Self::Error> {
+        p.to_string().parse()
+    }
+}
+
+/// Builder for [`Params`] that validates on [`build`](Self::build).
+#[derive(Clone, Debug, Default)]
+pub struct ParamsBuilder {
+    params: Params,
+    error: Option<ParamError>,
+}
+
+macro_rules! setters {
+    ($($(#[$doc:meta])* $name:ident: $ty:ty,)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self, value: $ty) -> Self {
+                self.params.$name = Some(value);
+                self
+            }
+        )*
+    };
+}
+
+impl ParamsBuilder {
+    setters! {
+        objective: Objective,
+        num_class: usize,
+        num_iterations: usize,
+        learning_rate: f64,
+        num_leaves: usize,
+        /// `<= 0` means no limit.
+        max_depth: i32,
+        min_data_in_leaf: usize,
+        min_sum_hessian_in_leaf: f64,
+        bagging_fraction: f64,
+        bagging_freq: usize,
+        feature_fraction: f64,
+        lambda_l1: f64,
+        lambda_l2: f64,
+        /// `0` lets OpenMP pick.
+        num_threads: usize,
+        seed: i64,
+        verbosity: Verbosity,
+    }
+
+    /// Adds an evaluation metric.
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.params
+            .metrics
+            .get_or_insert_with(Vec::new)
+            .push(metric);
+        self
+    }
+
+    /// Replaces the evaluation metrics; an empty list disables evaluation.
+    pub fn metrics(mut self, metrics: impl IntoIterator<Item = Metric>) -> Self {
+        self.params.metrics = Some(metrics.into_iter().collect());
+        self
+    }
+
+    /// Sets any parameter by LightGBM name, see [`Params::set`].
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        if self.error.is_none()
+            && let Err(e) = self.params.set(key, &value.to_string())
+        {
+            self.error = Some(e);
+        }
+        self
+    }
+
+    pub fn build(self) -> Result<Params, ParamError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, ParamError> {
+    value
+        .parse()
+        .map_err(|_| ParamError::new(key, format!("invalid value `{value}`")))
+}
+
+fn parse_objective(value: &str) -> Result<Objective, ParamError> {
+    Ok(match value.to_ascii_lowercase().as_str() {
+        "regression"
+        | "regression_l2"
+        | "l2"
+        | "mean_squared_error"
+        | "mse"
+        | "l2_root"
+        | "root_mean_squared_error"
+        | "rmse" => Objective::Regression,
+        "regression_l1" | "l1" | "mean_absolute_error" | "mae" => Objective::RegressionL1,
+        "huber" => Objective::Huber,
+        "fair" => Objective::Fair,
+        "poisson" => Objective::Poisson,
+        "quantile" => Objective::Quantile,
+        "mape" | "mean_absolute_percentage_error" => Objective::Mape,
+        "gamma" => Objective::Gamma,
+        "tweedie" => Objective::Tweedie,
+        "binary" => Objective::Binary,
+        "multiclass" | "softmax" => Objective::Multiclass,
+        "multiclassova" | "multiclass_ova" | "ova" | "ovr" => Objective::Multiclassova,
+        "cross_entropy" | "xentropy" => Objective::CrossEntropy,
+        "cross_entropy_lambda" | "xentlambda" => Objective::CrossEntropyLambda,
+        "lambdarank" => Objective::Lambdarank,
+        "rank_xendcg" | "xendcg" | "xe_ndcg" | "xe_ndcg_mart" | "xendcg_mart" => {
+            Objective::RankXendcg
+        }
+        "custom" | "none" | "null" | "na" => Objective::Custom,
+        _ => {
+            return Err(ParamError::new(
+                "objective",
+                format!("unknown objective `{value}`"),
+            ));
+        }
+    })
+}
+
+pub(crate) fn parse_metrics(value: &str) -> Result<Vec<Metric>, ParamError> {
+    let mut metrics = Vec::new();
+    for name in value.split(',').filter(|n| !n.is_empty()) {
+        let metric = match name.to_ascii_lowercase().as_str() {
+            "none" | "null" | "na" | "custom" => continue,
+            "l1" | "mean_absolute_error" | "mae" | "regression_l1" => Metric::L1,
+            "l2" | "mean_squared_error" | "mse" | "regression_l2" | "regression" => Metric::L2,
+            "rmse" | "root_mean_squared_error" | "l2_root" => Metric::Rmse,
+            "mape" | "mean_absolute_percentage_error" => Metric::Mape,
+            "ndcg" | "lambdarank" | "rank_xendcg" | "xendcg" | "xe_ndcg" | "xe_ndcg_mart"
+            | "xendcg_mart" => Metric::Ndcg,
+            "map" | "mean_average_precision" => Metric::Map,
+            "binary_logloss" | "binary" => Metric::BinaryLogloss,
+            "multi_logloss" | "multiclass" | "softmax" | "multiclassova" | "multiclass_ova"
+            | "ova" | "ovr" => Metric::MultiLogloss,
+            "cross_entropy" | "xentropy" => Metric::CrossEntropy,
+            "cross_entropy_lambda" | "xentlambda" => Metric::CrossEntropyLambda,
+            "kullback_leibler" | "kldiv" => Metric::KullbackLeibler,
+            other => other
+                .parse()
+                .map_err(|_| ParamError::new("metric", format!("unknown metric `{name}`")))?,
+        };
+        if !metrics.contains(&metric) {
+            metrics.push(metric);
+        }
+    }
+    Ok(metrics)
+}
+
+fn render_metrics(metrics: &[Metric]) -> String {
+    if metrics.is_empty() {
+        return "None".to_string();
+    }
+    metrics
+        .iter()
+        .map(Metric::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// LightGBM's `verbosity` levels, -1 to 2. Others are rejected rather than
+/// clamped, as they would not round-trip.
+fn parse_verbosity(value: &str) -> Result<Verbosity, ParamError> {
+    Ok(match parse::<i64>("verbosity", value)? {
+        -1 => Verbosity::Fatal,
+        0 => Verbosity::Error,
+        1 => Verbosity::Info,
+        2 => Verbosity::Debug,
+        _ => {
+            return Err(ParamError::new(
+                "verbosity",
+                format!("invalid value `{value}`; expected -1, 0, 1 or 2"),
+            ));
+        }
+    })
+}
+
+fn verbosity_to_int(verbosity: Verbosity) -> i64 {
+    match verbosity {
+        Verbosity::Fatal => -1,
+        Verbosity::Error => 0,
+        Verbosity::Info => 1,
+        Verbosity::Debug => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_round_trip() {
+        let s = "objective=binary metric=auc,binary_logloss num_iterations=50 learning_rate=0.1 \
+                 num_leaves=31 max_depth=-1 bagging_fraction=0.8 bagging_freq=1 seed=7 \
+                 verbosity=-1 max_bin=63";
+        let params = s.parse::<Params>().unwrap();
+        assert_eq!(params.objective, Some(Objective::Binary));
+        assert_eq!(
+            params.metrics,
+            Some(vec![Metric::Auc, Metric::BinaryLogloss])
+        );
+        assert_eq!(params.max_depth, Some(-1));
+        assert_eq!(params.verbosity, Some(Verbosity::Fatal));
+        assert_eq!(params.extra, [("max_bin".to_string(), "63".to_string())]);
+        assert_eq!(
+            params.to_string(),
+            s.split_whitespace().collect::<Vec<_>>().join(" ")
+        );
+        assert_eq!(params.to_string().parse::<Params>().unwrap(), params);
+        let p = params.to_parameters().unwrap();
+        assert_eq!(Params::try_from(&p).unwrap(), params);
+    }
+
+    #[test]
+    fn empty_metric_list_disables_evaluation() {
+        let params = "metric=None".parse::<Params>().unwrap();
+        assert_eq!(params.metrics, Some(vec![]));
+        assert_eq!(params.to_string(), "metric=None");
+    }
+
+    #[test]
+    fn aliases_map_to_canonical_names() {
+        let params = "eta=0.3 n_estimators=20 num_leaf=7 reg_lambda=2 application=xentropy"
+            .parse::<Params>()
+            .unwrap();
+        assert_eq!(params.learning_rate, Some(0.3));
+        assert_eq!(params.num_iterations, Some(20));
+        assert_eq!(params.num_leaves, Some(7));
+        assert_eq!(params.lambda_l2, Some(2.0));
+        assert_eq!(params.objective, Some(Objective::CrossEntropy));
+        assert!(params.contains("shrinkage_rate"));
+        assert_eq!(
+            params.to_string(),
+            "objective=cross_entropy num_iterations=20 learning_rate=0.3 num_leaves=7 lambda_l2=2"
+        );
+    }
+
+    #[test]
+    fn alias_set_twice_is_an_error() {
+        let mut params = Params::default();
+        params.set("learning_rate", "0.1").unwrap();
+        let e = params.set("eta", "0.2").unwrap_err();
+        assert_eq!(e.key, "learning_rate");
+        params.replace("shrinkage_rate", "0.2").unwrap();
+        assert_eq!(params.learning_rate, Some(0.2));
+        params.remove("eta");
+        assert!(!params.contains("learning_rate"));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert!("num_leaves".parse::<Params>().is_err());
+        assert_eq!(
+            "num_leaves=ten".parse::<Params>().unwrap_err().key,
+            "num_leaves"
+        );
+        assert_eq!(
+            "objective=magic".parse::<Params>().unwrap_err().key,
+            "objective"
+        );
+        assert!(Params::default().set("a b", "1").is_err());
+        assert!(Params::default().set("max_bin", "6 3").is_err());
+    }
+
+    #[test]
+    fn verbosity_outside_lightgbm_levels_is_rejected() {
+        for level in ["-1", "0", "1", "2"] {
+            let params = format!("verbosity={level}").parse::<Params>().unwrap();
+            assert_eq!(params.to_string(), format!("verbosity={level}"));
+        }
+        for level in ["-2", "3"] {
+            let e = format!("verbose={level}").parse::<Params>().unwrap_err();
+            assert_eq!(e.key, "verbosity");
+        }
+    }
+
+    #[test]
+    fn validate_checks_ranges() {
+        for s in [
+            "learning_rate=0",
+            "learning_rate=-0.1",
+            "num_leaves=1",
+            "feature_fraction=0",
+            "bagging_fraction=1.5",
+            "lambda_l1=-1",
+            "min_sum_hessian_in_leaf=-1",
+            "bagging_fraction=0.5",
+        ] {
+            assert!(s.parse::<Params>().is_err(), "{s} accepted");
+        }
+        assert!(
+            "bagging_fraction=0.5 bagging_freq=1"
+                .parse::<Params>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn validate_checks_num_class() {
+        assert_eq!(
+            "objective=multiclass".parse::<Params>().unwrap_err().key,
+            "num_class"
+        );
+        assert!(
+            "objective=multiclass num_class=1"
+                .parse::<Params>()
+                .is_err()
+        );
+        assert!("objective=multiclass num_class=3".parse::<Params>().is_ok());
+        assert!("objective=binary num_class=3".parse::<Params>().is_err());
+        assert!("objective=binary num_class=1".parse::<Params>().is_ok());
+        assert!("objective=custom num_class=0".parse::<Params>().is_err());
+        assert!("objective=custom num_class=1".parse::<Params>().is_ok());
+        assert!(
+            "objective=custom num_class=3 metric=multi_logloss"
+                .parse::<Params>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn validate_checks_metrics_against_objective() {
+        assert!(
+            "objective=binary metric=multi_logloss"
+                .parse::<Params>()
+                .is_err()
+        );
+        assert!(
+            "objective=multiclass num_class=3 metric=l2"
+                .parse::<Params>()
+                .is_err()
+        );
+        assert!(
+            "objective=multiclass num_class=3 metric=multi_error"
+                .parse::<Params>()
+                .is_ok()
+        );
+        assert!(
+            "objective=regression metric=ndcg"
+                .parse::<Params>()
+                .is_err()
+        );
+        assert!(
+            "objective=lambdarank metric=ndcg,map"
+                .parse::<Params>()
+                .is_ok()
+        );
+        assert!("objective=custom metric=ndcg,map".parse::<Params>().is_ok());
+    }
+
+    #[test]
+    fn builder_reports_first_error() {
+        let e = Params::builder()
+            .param("num_leaves", "x")
+            .param("learning_rate", "y")
+            .build()
+            .unwrap_err();
+        assert_eq!(e.key, "num_leaves");
+        assert!(
+            Params::builder()
+                .objective(Objective::Regression)
+                .learning_rate(0.0)
+                .build()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use lgbm::{AsMat, Booster, FeatureData, Parameters, PredictType, Prediction, Result};
+
+/// Prediction output as a matrix with one row per input row.
+///
+/// The number of columns depends on the prediction type and is taken from
+/// LightGBM rather than assumed:
+///
+/// - [`PredictType::Normal`] and [`PredictType::RawScore`]: one score per
+///   class (a single column unless multiclass).
+/// - [`PredictType::LeafIndex`]: one leaf per tree, `num_class` trees per
+///   iteration.
+/// - [`PredictType::Contrib`]: per class, one contribution per feature
+///   followed by the expected value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Predictions {
+    predict_type: PredictType,
+    num_rows: usize,
+    num_cols: usize,
+    num_class: usize,
+    values: Vec<f64>,
+}
+
+impl Predictions {
+    /// Predicts `mat` with `booster`; see [`Booster::predict_for_mat`].
+    pub fn predict<T: FeatureData>(
+        booster: &Booster,
+        mat: impl AsMat<T>,
+        predict_type: PredictType,
+        start_iteration: usize,
+        num_iteration: Option<usize>,
+        parameters: &Parameters,
+    ) -> Result<Self> {
+        let prediction = booster.predict_for_mat(
+            mat,
+            predict_type,
+            start_iteration,
+            num_iteration,
+            parameters,
+        )?;
+        Self::new(&prediction, predict_type)
+    }
+
+    /// Wraps a prediction made with `predict_type`.
+    pub fn new(prediction: &Prediction, predict_type: PredictType) -> Result<Self> {
+        Self::from_values(
+            prediction.values().to_vec(),
+            prediction.num_data(),
+            prediction.num_class(),
+            predict_type,
+        )
+    }
+
+    /// Row-major `values` for `num_rows` rows of a model with `num_class`
+    /// classes. Fails unless every row holds the same number of values, a
+    /// multiple of `num_class`.
+    pub(crate) fn from_values(
+        values: Vec<f64>,
+        num_rows: usize,
+        num_class: usize,
+        predict_type: PredictType,
+    ) -> Result<Self> {
+        let num_cols = values.len().checked_div(num_rows).unwrap_or(0);
+        if num_rows * num_cols != values.len()
+            || num_class == 0
+            || !num_cols.is_multiple_of(num_class)
+        {
+            return Err(lgbm::Error::from_message(&format!(
+                "{} prediction values do not split into {num_rows} rows of {num_class} classes",
+                values.len()
+            )));
+        }
+        Ok(Self {
+            predict_type,
+            num_rows,
+            num_cols,
+            num_class,
+            values,
+        })
+    }
+
+    pub fn predict_type(&self) -> PredictType {
+        self.predict_type
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Values per row.
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// Number of classes of the model.
+    pub fn num_class(&self) -> usize {
+        self.num_class
+    }
+
+    /// All values, row by row.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    pub fn row(&self, row: usize) -> &[f64] {
+        &self.values[row * self.num_cols..(row + 1) * self.num_cols]
+    }
+
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[f64]> {
+        // `chunks_exact` panics on a zero chunk size.
+        self.values
+            .chunks_exact(self.num_cols.max(1))
+            .take(self.num_rows)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(col < self.num_cols, "column {col} out of {}", self.num_cols);
+        self.values[row * self.num_cols + col]
+    }
+
+    /// Column with the highest value in every row, i.e. the predicted class
+    /// for multiclass scores. Single-column scores always give column 0.
+    pub fn argmax(&self) -> Vec<usize> {
+        self.rows()
+            .map(|row| {
+                (0..row.len())
+                    .max_by(|&a, &b| row[a].total_cmp(&row[b]).then(b.cmp(&a)))
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Up to `k` `(column, value)` pairs per row, highest value first; ties
+    /// keep column order.
+    pub fn top_k(&self, k: usize) -> Vec<Vec<(usize, f64)>> {
+        self.rows()
+            .map(|row| {
+                let mut columns = (0..row.len()).collect::<Vec<_>>();
+                columns.sort_by(|&a, &b| row[b].total_cmp(&row[a]));
+                columns.into_iter().take(k).map(|c| (c, row[c])).collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(rows: &[&[f64]]) -> Predictions {
+        let num_class = rows[0].len();
+        let values = rows.iter().flat_map(|row| row.iter().copied()).collect();
+        Predictions::from_values(values, rows.len(), num_class, PredictType::Normal).unwrap()
+    }
+
+    #[test]
+    fn rows_split_values_by_columns() {
+        let p = scores(&[&[0.1, 0.7, 0.2], &[0.5, 0.3, 0.2]]);
+        assert_eq!((p.num_rows(), p.num_cols(), p.num_class()), (2, 3, 3));
+        let rows = p.rows().collect::<Vec<_>>();
+        assert_eq!(rows, [&[0.1, 0.7, 0.2][..], &[0.5, 0.3, 0.2][..]]);
+        assert_eq!(p.row(1), &[0.5, 0.3, 0.2]);
+        assert_eq!(p.get(0, 1), 0.7);
+    }
+
+    #[test]
+    fn contributions_have_a_multiple_of_num_class_columns() {
+        // Two classes, two features plus the expected value each.
+        let p = Predictions::from_values(vec![0.0; 12], 2, 2, PredictType::Contrib).unwrap();
+        assert_eq!((p.num_cols(), p.rows().len()), (6, 2));
+    }
+
+    #[test]
+    fn shape_errors() {
+        // 7 values do not split into 2 rows.
+        assert!(Predictions::from_values(vec![0.0; 7], 2, 1, PredictType::Normal).is_err());
+        // 3 values per row are not a multiple of 2 classes.
+        assert!(Predictions::from_values(vec![0.0; 6], 2, 2, PredictType::Normal).is_err());
+        assert!(Predictions::from_values(vec![0.0; 6], 2, 0, PredictType::Normal).is_err());
+    }
+
+    #[test]
+    fn no_rows() {
+        let p = Predictions::from_values(Vec::new(), 0, 3, PredictType::Normal).unwrap();
+        assert_eq!(p.rows().len(), 0);
+        assert!(p.argmax().is_empty());
+        assert!(p.top_k(2).is_empty());
+    }
+
+    #[test]
+    fn argmax_picks_the_first_of_tied_columns() {
+        let p = scores(&[&[0.1, 0.7, 0.2], &[0.4, 0.2, 0.4], &[0.3, 0.3, 0.3]]);
+        assert_eq!(p.argmax(), [1, 0, 0]);
+    }
+
+    #[test]
+    fn argmax_of_single_column_is_zero() {
+        assert_eq!(scores(&[&[0.9], &[0.1]]).argmax(), [0, 0]);
+    }
+
+    #[test]
+    fn top_k_sorts_descending_and_keeps_column_order_on_ties() {
+        let p = scores(&[&[0.1, 0.4, 0.1, 0.4]]);
+        assert_eq!(p.top_k(3), [vec![(1, 0.4), (3, 0.4), (0, 0.1)]]);
+    }
+
+    #[test]
+    fn top_k_beyond_num_class_returns_every_column() {
+        let p = scores(&[&[0.2, 0.5, 0.3], &[0.6, 0.3, 0.1]]);
+        assert_eq!(
+            p.top_k(5),
+            [
+                vec![(1, 0.5), (2, 0.3), (0, 0.2)],
+                vec![(0, 0.6), (1, 0.3), (2, 0.1)]
+            ]
+        );
+    }
+
+    #[test]
+    fn top_zero_is_empty_per_row() {
+        let p = scores(&[&[0.2, 0.8], &[0.6, 0.4]]);
+        assert_eq!(p.top_k(0), [Vec::new(), Vec::new()]);
+    }
+}
@@ -0,0 +1,60 @@
+use lgbm::Result;
+use std::{collections::HashMap, hash::Hash};
+
+/// Predictions of one query, best first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankedQuery<Q> {
+    pub query: Q,
+    /// Input rows of the query, ordered by descending score.
+    pub rows: Vec<usize>,
+    /// Scores of `rows`.
+    pub scores: Vec<f64>,
+}
+
+/// Groups one score per row by query id and ranks every query by score.
+///
+/// Queries appear in the order of their first row; rows need not be sorted
+/// by query. Ties keep input order. Fails unless there is one score per
+/// query id.
+///
+/// ```
+/// # use lightgbm_static::group_by_query;
+/// let ranked = group_by_query(&["a", "b", "a"], &[0.1, 0.5, 0.7])?;
+/// assert_eq!(ranked[0].query, "a");
+/// assert_eq!(ranked[0].rows, [2, 0]);
+/// assert_eq!(ranked[1].scores, [0.5]);
+/// # Ok::<(), lgbm::Error>(())
+/// ```
+pub fn group_by_query<Q: Hash + Eq + Clone>(
+    query_ids: &[Q],
+    scores: &[f64],
+) -> Result<Vec<RankedQuery<Q>>> {
+    if query_ids.len() != scores.len() {
+        return Err(lgbm::Error::from_message(&format!(
+            "{} scores for {} query ids",
+            scores.len(),
+            query_ids.len()
+        )));
+    }
+    let mut index = HashMap::new();
+    let mut queries = Vec::<RankedQuery<Q>>::new();
+    for (row, (query, &score)) in query_ids.iter().zip(scores).enumerate() {
+        let i = *index.entry(query).or_insert_with(|| {
+            queries.push(RankedQuery {
+                query: query.clone(),
+                rows: Vec::new(),
+                scores: Vec::new(),
+            });
+            queries.len() - 1
+        });
+        queries[i].rows.push(row);
+        queries[i].scores.push(score);
+    }
+    for query in &mut queries {
+        let mut order = (0..query.rows.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| query.scores[b].total_cmp(&query.scores[a]));
+        query.rows = order.iter().map(|&i| query.rows[i]).collect();
+        query.scores = order.iter().map(|&i| query.scores[i]).collect();
+    }
+    Ok(queries)
+}
@@ -0,0 +1,581 @@
+use crate::{
+    CvResult, ParamError, Params,
+    cv::SplitMix64,
+    higher_is_better,
+    params::{ParameterKind, parameter_kind},
+};
+use std::str::FromStr;
+
+/// Values a searched parameter is drawn from.
+///
+/// Parsed from `int:<min>:<max>`, `float:<min>:<max>` (either with a trailing
+/// `:log` for log-uniform sampling) or `choice:<a>,<b>,...`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Distribution {
+    /// Integers in `min..=max`.
+    Int {
+        min: i64,
+        max: i64,
+        log: bool,
+    },
+    /// Floats in `min..max`.
+    Float {
+        min: f64,
+        max: f64,
+        log: bool,
+    },
+    Choice(Vec<String>),
+}
+
+impl Distribution {
+    fn sample(&self, rng: &mut SplitMix64) -> String {
+        let uniform = |rng: &mut SplitMix64, min: f64, max: f64, log: bool| {
+            if log {
+                (min.ln() + rng.next_f64() * (max.ln() - min.ln())).exp()
+            } else {
+                min + rng.next_f64() * (max - min)
+            }
+        };
+        match self {
+            Distribution::Int { min, max, log } => {
+                // Sample [min, max + 1) so that `max` is as likely as `min`.
+                let value = uniform(rng, *min as f64, (*max + 1) as f64, *log).floor() as i64;
+                value.clamp(*min, *max).to_string()
+            }
+            Distribution::Float { min, max, log } => uniform(rng, *min, *max, *log).to_string(),
+            Distribution::Choice(values) => values[rng.below(values.len())].clone(),
+        }
+    }
+}
+
+impl FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, args) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected `int:`, `float:` or `choice:`, got `{s}`"))?;
+        if kind == "choice" {
+            let values = args
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<_>>();
+            if values.is_empty() {
+                return Err("`choice:` needs at least one value".to_string());
+            }
+            return Ok(Distribution::Choice(values));
+        }
+
+        let parts = args.split(':').collect::<Vec<_>>();
+        let (min, max, log) = match parts[..] {
+            [min, max] => (min, max, false),
+            [min, max, "log"] => (min, max, true),
+            _ => return Err(format!("expected `{kind}:<min>:<max>[:log]`, got `{s}`")),
+        };
+        let distribution = match kind {
+            "int" => Distribution::Int {
+                min: min
+                    .parse()
+                    .map_err(|_| format!("invalid integer `{min}`"))?,
+                max: max
+                    .parse()
+                    .map_err(|_| format!("invalid integer `{max}`"))?,
+                log,
+            },
+            "float" => Distribution::Float {
+                min: min.parse().map_err(|_| format!("invalid number `{min}`"))?,
+                max: max.parse().map_err(|_| format!("invalid number `{max}`"))?,
+                log,
+            },
+            _ => return Err(format!("unknown distribution `{kind}`")),
+        };
+        let (min, max) = match distribution {
+            Distribution::Int { min, max, .. } => (min as f64, max as f64),
+            Distribution::Float { min, max, .. } => (min, max),
+            Distribution::Choice(_) => unreachable!(),
+        };
+        if !min.is_finite() || !max.is_finite() || min > max || (log && min <= 0.0) {
+            return Err(format!("invalid range in `{s}`"));
+        }
+        Ok(distribution)
+    }
+}
+
+/// Parameters to search and the distributions they are drawn from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchSpace {
+    params: Vec<(String, Distribution)>,
+}
+
+impl SearchSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a parameter; each may only be added once. Prediction parameters
+    /// are rejected, as they do not change cross-validation scores.
+    pub fn add(&mut self, key: &str, distribution: Distribution) -> Result<(), ParamError> {
+        let key = key.trim();
+        match parameter_kind(key) {
+            None => return Err(ParamError::new(key, "unknown parameter")),
+            Some(ParameterKind::Prediction) => {
+                return Err(ParamError::new(
+                    key,
+                    "only affects prediction, not training",
+                ));
+            }
+            Some(_) => {}
+        }
+        if self.params.iter().any(|(k, _)| k == key) {
+            return Err(ParamError::new(key, "searched more than once"));
+        }
+        self.params.push((key.to_string(), distribution));
+        Ok(())
+    }
+
+    /// Adds `key=<distribution>`, e.g. `num_leaves=int:8:256`.
+    pub fn add_str(&mut self, param: &str) -> Result<(), ParamError> {
+        let Some((key, distribution)) = param.split_once('=') else {
+            return Err(ParamError::new(param, "expected `key=<distribution>`"));
+        };
+        let distribution = distribution
+            .parse()
+            .map_err(|message: String| ParamError::new(key.trim(), message))?;
+        self.add(key, distribution)
+    }
+
+    /// Names of the searched parameters, in the order they were added.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(|(key, _)| key.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    fn sample(&self, rng: &mut SplitMix64) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .map(|(key, distribution)| (key.clone(), distribution.sample(rng)))
+            .collect()
+    }
+}
+
+/// How [`Search`] spends its budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Evaluates `trials` random candidates with the full number of
+    /// iterations.
+    Random { trials: usize },
+    /// Evaluates `trials` random candidates with `min_iterations`, keeps the
+    /// best `1 / eta` of them, multiplies the iterations by `eta` and repeats
+    /// until one candidate is left or the full number of iterations is
+    /// reached.
+    SuccessiveHalving {
+        trials: usize,
+        min_iterations: usize,
+        eta: usize,
+    },
+}
+
+/// One evaluated candidate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trial {
+    /// Candidate number; the same candidate keeps its id across rungs.
+    pub id: usize,
+    /// Successive halving round, 0 for random search.
+    pub rung: usize,
+    /// Sampled values of the searched parameters.
+    pub params: Vec<(String, String)>,
+    /// Iteration budget the candidate was cross-validated with.
+    pub num_iterations: usize,
+    pub best_iteration: usize,
+    pub metric: String,
+    /// Mean of `metric` over the folds at `best_iteration`.
+    pub score: f64,
+}
+
+/// Outcome of a [`Search`].
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    trials: Vec<Trial>,
+    best: usize,
+}
+
+impl SearchResult {
+    pub fn trials(&self) -> &[Trial] {
+        &self.trials
+    }
+
+    /// Best trial of the last rung.
+    pub fn best(&self) -> &Trial {
+        &self.trials[self.best]
+    }
+
+    /// `base` with the best trial's parameters and its best iteration as
+    /// `num_iterations`.
+    pub fn best_params(&self, base: &Params) -> Result<Params, ParamError> {
+        let best = self.best();
+        let mut params = base.clone();
+        for (key, value) in &best.params {
+            params.replace(key, value)?;
+        }
+        params.replace("num_iterations", &best.best_iteration.to_string())?;
+        Ok(params)
+    }
+}
+
+/// Hyperparameter search over a [`SearchSpace`] scored by cross-validation.
+pub struct Search {
+    space: SearchSpace,
+    strategy: Strategy,
+    seed: u64,
+}
+
+impl Search {
+    pub fn new(space: SearchSpace, strategy: Strategy) -> Self {
+        Self {
+            space,
+            strategy,
+            seed: 0,
+        }
+    }
+
+    /// Seed for sampling candidates.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Runs the search on top of `base`, cross-validating every candidate
+    /// with `evaluate`.
+    ///
+    /// `evaluate` receives `base` with the candidate's parameters and its
+    /// iteration budget set as `num_iterations`. `on_trial` is called after
+    /// every evaluation, e.g. to record it.
+    pub fn run(
+        &self,
+        base: &Params,
+        num_iterations: usize,
+        mut evaluate: impl FnMut(&Params) -> lgbm::Result<CvResult>,
+        mut on_trial: impl FnMut(&Trial) -> lgbm::Result<()>,
+    ) -> lgbm::Result<SearchResult> {
+        let (num_candidates, mut budget, eta) = match self.strategy {
+            Strategy::Random { trials } => (trials, num_iterations, 1),
+            Strategy::SuccessiveHalving {
+                trials,
+                min_iterations,
+                eta,
+            } => (trials, min_iterations.clamp(1, num_iterations), eta.max(2)),
+        };
+        if num_candidates == 0 || num_iterations == 0 {
+            return Err(lgbm::Error::from_message(
+                "search needs at least one trial and one iteration",
+            ));
+        }
+
+        let mut rng = SplitMix64::new(self.seed);
+        let mut candidates = (0..num_candidates)
+            .map(|id| (id, self.space.sample(&mut rng)))
+            .collect::<Vec<_>>();
+        let mut trials = Vec::new();
+        for rung in 0.. {
+            let start = trials.len();
+            for (id, sampled) in &candidates {
+                let mut params = base.clone();
+                for (key, value) in sampled {
+                    params.replace(key, value)?;
+                }
+                params.replace("num_iterations", &budget.to_string())?;
+                params.validate()?;
+                let result = evaluate(&params)?;
+                let trial = Trial {
+                    id: *id,
+                    rung,
+                    params: sampled.clone(),
+                    num_iterations: budget,
+                    best_iteration: result.best_iteration(),
+                    metric: result.metric().to_string(),
+                    score: result.best_score(),
+                };
+                on_trial(&trial)?;
+                trials.push(trial);
+            }
+
+            let mut ranked = (start..trials.len()).collect::<Vec<_>>();
+            ranked.sort_by(|&a, &b| compare(&trials[a], &trials[b]));
+            if candidates.len() == 1 || budget >= num_iterations {
+                return Ok(SearchResult {
+                    best: ranked[0],
+                    trials,
+                });
+            }
+            let keep = (candidates.len() / eta).max(1);
+            candidates = ranked[..keep]
+                .iter()
+                .map(|&i| (trials[i].id, trials[i].params.clone()))
+                .collect();
+            budget = budget.saturating_mul(eta).min(num_iterations);
+        }
+        unreachable!()
+    }
+}
+
+/// Orders trials best first; NaN scores last.
+fn compare(a: &Trial, b: &Trial) -> std::cmp::Ordering {
+    let key = |t: &Trial| {
+        if t.score.is_nan() {
+            f64::INFINITY
+        } else if higher_is_better(&t.metric) {
+            -t.score
+        } else {
+            t.score
+        }
+    };
+    key(a).total_cmp(&key(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution_from_str() {
+        assert_eq!(
+            "int:8:256".parse(),
+            Ok(Distribution::Int {
+                min: 8,
+                max: 256,
+                log: false
+            })
+        );
+        assert_eq!(
+            "float:0.01:0.3:log".parse(),
+            Ok(Distribution::Float {
+                min: 0.01,
+                max: 0.3,
+                log: true
+            })
+        );
+        assert_eq!(
+            "choice: gbdt, dart ,".parse(),
+            Ok(Distribution::Choice(vec![
+                "gbdt".to_string(),
+                "dart".to_string()
+            ]))
+        );
+        assert_eq!(
+            "int:5:5".parse(),
+            Ok(Distribution::Int {
+                min: 5,
+                max: 5,
+                log: false
+            })
+        );
+    }
+
+    #[test]
+    fn distribution_from_str_rejects_invalid() {
+        for s in [
+            "int",
+            "normal:0:1",
+            "int:1",
+            "int:1:2:3",
+            "int:1:2:linear",
+            "int:1.5:2",
+            "float:a:1",
+            "int:10:1",
+            "float:0:1:log",
+            "float:-1:1:log",
+            "float:0:inf",
+            "choice:",
+            "choice: , ",
+        ] {
+            assert!(s.parse::<Distribution>().is_err(), "{s} accepted");
+        }
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        let mut rng = SplitMix64::new(0);
+        let int = "int:1:3".parse::<Distribution>().unwrap();
+        let mut seen = [false; 3];
+        for _ in 0..200 {
+            let v = int.sample(&mut rng).parse::<i64>().unwrap();
+            assert!((1..=3).contains(&v), "{v}");
+            seen[v as usize - 1] = true;
+        }
+        assert_eq!(seen, [true; 3]);
+
+        let float = "float:0.001:1:log".parse::<Distribution>().unwrap();
+        for _ in 0..200 {
+            let v = float.sample(&mut rng).parse::<f64>().unwrap();
+            assert!((0.001..1.0).contains(&v), "{v}");
+        }
+    }
+
+    #[test]
+    fn search_space_rejects_unknown_and_repeated_keys() {
+        let mut space = SearchSpace::new();
+        space.add_str("num_leaves=int:8:64").unwrap();
+        assert!(space.add_str("num_leaves=int:8:64").is_err());
+        assert!(space.add_str("no_such_param=int:1:2").is_err());
+        assert!(space.add_str("learning_rate").is_err());
+        assert!(
+            space
+                .add_str("predict_raw_score=choice:true,false")
+                .is_err()
+        );
+        space.add_str("max_bin=int:15:255").unwrap();
+        assert_eq!(
+            space.add_str("lambda_l1=int:2:1").unwrap_err().key,
+            "lambda_l1"
+        );
+        assert_eq!(space.keys().collect::<Vec<_>>(), ["num_leaves", "max_bin"]);
+    }
+
+    /// Runs `strategy` over `learning_rate`, scoring every candidate by its
+    /// sampled rate (lower is better), and returns the trials.
+    fn run(strategy: Strategy, num_iterations: usize) -> SearchResult {
+        let mut space = SearchSpace::new();
+        space.add_str("learning_rate=float:0.01:1").unwrap();
+        let mut recorded = 0;
+        let result = Search::new(space, strategy)
+            .seed(42)
+            .run(
+                &Params::default(),
+                num_iterations,
+                |params| {
+                    let budget = params.num_iterations.unwrap();
+                    assert!(budget <= num_iterations);
+                    Ok(CvResult::from_best(
+                        "l2",
+                        (budget, params.learning_rate.unwrap()),
+                    ))
+                },
+                |_| {
+                    recorded += 1;
+                    Ok(())
+                },
+            )
+            .unwrap();
+        assert_eq!(recorded, result.trials().len());
+        result
+    }
+
+    /// `(rung, candidates, budget)` of every rung.
+    fn rungs(result: &SearchResult) -> Vec<(usize, usize, usize)> {
+        let mut rungs = Vec::<(usize, usize, usize)>::new();
+        for trial in result.trials() {
+            match rungs.last_mut() {
+                Some((rung, count, budget)) if *rung == trial.rung => {
+                    assert_eq!(*budget, trial.num_iterations);
+                    *count += 1;
+                }
+                _ => rungs.push((trial.rung, 1, trial.num_iterations)),
+            }
+        }
+        rungs
+    }
+
+    #[test]
+    fn successive_halving_multiplies_budget() {
+        let strategy = Strategy::SuccessiveHalving {
+            trials: 9,
+            min_iterations: 10,
+            eta: 3,
+        };
+        let result = run(strategy, 100);
+        assert_eq!(rungs(&result), [(0, 9, 10), (1, 3, 30), (2, 1, 90)]);
+
+        // Every rung keeps the best third of the previous one.
+        let best_of = |rung: usize, n: usize| {
+            let mut trials = result
+                .trials()
+                .iter()
+                .filter(|t| t.rung == rung)
+                .collect::<Vec<_>>();
+            trials.sort_by(|a, b| compare(a, b));
+            let mut ids = trials[..n].iter().map(|t| t.id).collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        let ids = |rung: usize| {
+            let mut ids = result
+                .trials()
+                .iter()
+                .filter(|t| t.rung == rung)
+                .map(|t| t.id)
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(1), best_of(0, 3));
+        assert_eq!(ids(2), best_of(1, 1));
+        assert_eq!(result.best().rung, 2);
+        assert_eq!(result.best().id, ids(2)[0]);
+    }
+
+    #[test]
+    fn successive_halving_caps_budget() {
+        let strategy = Strategy::SuccessiveHalving {
+            trials: 9,
+            min_iterations: 10,
+            eta: 3,
+        };
+        assert_eq!(
+            rungs(&run(strategy, 50)),
+            [(0, 9, 10), (1, 3, 30), (2, 1, 50)]
+        );
+        // Stops once the full budget is reached, even with candidates left.
+        assert_eq!(rungs(&run(strategy, 30)), [(0, 9, 10), (1, 3, 30)]);
+        // `min_iterations` is clamped to the full budget and `eta` to 2.
+        let strategy = Strategy::SuccessiveHalving {
+            trials: 4,
+            min_iterations: 0,
+            eta: 1,
+        };
+        assert_eq!(rungs(&run(strategy, 3)), [(0, 4, 1), (1, 2, 2), (2, 1, 3)]);
+    }
+
+    #[test]
+    fn random_search_uses_full_budget() {
+        let result = run(Strategy::Random { trials: 5 }, 20);
+        assert_eq!(rungs(&result), [(0, 5, 20)]);
+        let best = result
+            .trials()
+            .iter()
+            .min_by(|a, b| a.score.total_cmp(&b.score))
+            .unwrap();
+        assert_eq!(result.best(), best);
+    }
+
+    #[test]
+    fn search_needs_trials_and_iterations() {
+        let space = SearchSpace::new();
+        let search = Search::new(space, Strategy::Random { trials: 0 });
+        assert!(
+            search
+                .run(&Params::default(), 10, |_| unreachable!(), |_| Ok(()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn compare_puts_nan_last_and_respects_direction() {
+        let trial = |metric: &str, score: f64| Trial {
+            id: 0,
+            rung: 0,
+            params: Vec::new(),
+            num_iterations: 1,
+            best_iteration: 1,
+            metric: metric.to_string(),
+            score,
+        };
+        use std::cmp::Ordering::Less;
+        assert_eq!(compare(&trial("l2", 1.0), &trial("l2", 2.0)), Less);
+        assert_eq!(compare(&trial("auc", 0.9), &trial("auc", 0.8)), Less);
+        assert_eq!(compare(&trial("auc", 0.1), &trial("auc", f64::NAN)), Less);
+    }
+}
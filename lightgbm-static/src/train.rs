@@ -0,0 +1,577 @@
+use crate::{
+    CallbackContext, Control, EarlyStopping, Explanation, InitModel, Metadata, Metric, Objective,
+    Predictions, TrainingCallback,
+    ffi::{RawBooster, RawDataset},
+    higher_is_better,
+    objective::check_gradients,
+};
+use lgbm::{AsMat, Booster, FeatureData, Parameters, PredictType, Prediction, Result};
+use std::{
+    cell::{OnceCell, RefCell},
+    ffi::CString,
+    fs, io,
+    path::Path,
+};
+
+/// One metric value on one dataset after one boosting iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    /// 1-based iteration the value was computed after.
+    pub iteration: usize,
+    pub dataset: String,
+    pub metric: String,
+    pub value: f64,
+}
+
+/// Every [`Evaluation`] of a training run, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvalHistory {
+    evaluations: Vec<Evaluation>,
+}
+
+impl EvalHistory {
+    #[cfg(test)]
+    pub(crate) fn from_evaluations(evaluations: Vec<Evaluation>) -> Self {
+        Self { evaluations }
+    }
+
+    pub fn evaluations(&self) -> &[Evaluation] {
+        &self.evaluations
+    }
+
+    /// Values of one metric on one dataset, indexed by iteration - 1.
+    pub fn values(&self, dataset: &str, metric: &str) -> Vec<f64> {
+        self.evaluations
+            .iter()
+            .filter(|e| e.dataset == dataset && e.metric == metric)
+            .map(|e| e.value)
+            .collect()
+    }
+
+    /// Evaluations of the given iteration.
+    pub fn iteration(&self, iteration: usize) -> impl Iterator<Item = &Evaluation> {
+        self.evaluations
+            .iter()
+            .filter(move |e| e.iteration == iteration)
+    }
+
+    /// Writes `iteration,dataset,metric,value` rows with a header line.
+    pub fn write_csv(&self, mut w: impl io::Write) -> io::Result<()> {
+        writeln!(w, "iteration,dataset,metric,value")?;
+        for e in &self.evaluations {
+            writeln!(w, "{},{},{},{}", e.iteration, e.dataset, e.metric, e.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why [`Trainer::update`] asks to stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// No further splits are possible; no tree was added.
+    NoSplits,
+    /// The early stopping metric has not improved for its patience.
+    EarlyStopping { best_iteration: usize },
+    /// A [`TrainingCallback`] returned [`Control::Stop`].
+    Callback,
+}
+
+/// Boosting loop over a LightGBM booster with named validation datasets.
+///
+/// The trainer builds its datasets from feature matrices and [`Metadata`];
+/// validation datasets share the bin mappers of the training dataset.
+pub struct Trainer {
+    // Declared before `datasets`, which it points into, so it is freed first.
+    booster: RawBooster,
+    /// The training dataset, then validation datasets, by data index.
+    datasets: Vec<RawDataset>,
+    /// Metadata of `datasets`, for custom objectives and metrics.
+    metadata: Vec<Metadata>,
+    /// Parameters validation datasets are built with.
+    parameters: Parameters,
+    valid_names: Vec<String>,
+    eval_names: Vec<String>,
+    eval_train: bool,
+    history: EvalHistory,
+    early_stopping: Option<EarlyStopping>,
+    callbacks: Vec<Box<dyn TrainingCallback>>,
+    init_model: Option<InitModel>,
+    /// The model up to the best iteration, loaded on first use after each
+    /// iteration.
+    model: OnceCell<Booster>,
+    objective: Option<Box<dyn Objective>>,
+    metrics: Vec<Box<dyn Metric>>,
+}
+
+#[derive(Clone, Copy)]
+enum Hook {
+    Before,
+    After,
+}
+
+impl Trainer {
+    /// Builds the training dataset from `features` and `metadata`, which must
+    /// have labels, and a booster on it.
+    pub fn new<T: FeatureData>(
+        features: impl AsMat<T>,
+        metadata: Metadata,
+        parameters: &Parameters,
+    ) -> Result<Self> {
+        let train = build_dataset(features, &metadata, None, parameters)?;
+        let booster = RawBooster::new(&train, parameters)?;
+        let eval_names = booster.eval_names()?;
+        Ok(Self {
+            booster,
+            datasets: vec![train],
+            metadata: vec![metadata],
+            parameters: parameters.clone(),
+            valid_names: Vec::new(),
+            eval_names,
+            eval_train: false,
+            history: EvalHistory::default(),
+            early_stopping: None,
+            callbacks: Vec::new(),
+            init_model: None,
+            model: OnceCell::new(),
+            objective: None,
+            metrics: Vec::new(),
+        })
+    }
+
+    /// Continues boosting on top of `init_model`, e.g. a [`Checkpoint`](crate::Checkpoint)
+    /// snapshot. Iterations are numbered on from those of the init model.
+    ///
+    /// The init scores of the training and validation datasets are set from
+    /// the init model's scores on their features, replacing any in their
+    /// metadata.
+    pub fn with_init_model<T: FeatureData>(
+        features: impl AsMat<T>,
+        mut metadata: Metadata,
+        parameters: &Parameters,
+        init_model: InitModel,
+    ) -> Result<Self> {
+        init_model.apply(&mut metadata, &features)?;
+        let mut trainer = Self::new(features, metadata, parameters)?;
+        trainer.init_model = Some(init_model);
+        Ok(trainer)
+    }
+
+    pub fn init_model(&self) -> Option<&InitModel> {
+        self.init_model.as_ref()
+    }
+
+    /// Iterations completed, including those of the init model.
+    pub fn current_iteration(&self) -> Result<usize> {
+        Ok(self.iteration_offset() + self.booster.current_iteration()?)
+    }
+
+    fn iteration_offset(&self) -> usize {
+        self.init_model
+            .as_ref()
+            .map_or(0, InitModel::num_iterations)
+    }
+
+    /// Adds a validation dataset, built from `features` and `metadata` like
+    /// the training dataset, evaluated after every iteration.
+    pub fn add_valid<T: FeatureData>(
+        &mut self,
+        name: impl Into<String>,
+        features: impl AsMat<T>,
+        mut metadata: Metadata,
+    ) -> Result<()> {
+        let name = name.into();
+        if name == "training" || self.valid_names.contains(&name) {
+            return Err(lgbm::Error::from_message(&format!(
+                "duplicate dataset name `{name}`"
+            )));
+        }
+        if let Some(init_model) = &self.init_model {
+            init_model.apply(&mut metadata, &features)?;
+        }
+        let dataset = build_dataset(
+            features,
+            &metadata,
+            Some(&self.datasets[0]),
+            &self.parameters,
+        )?;
+        self.booster.add_valid(&dataset)?;
+        self.datasets.push(dataset);
+        self.metadata.push(metadata);
+        self.valid_names.push(name);
+        Ok(())
+    }
+
+    /// Also evaluates the metrics on the training data, as `training`.
+    ///
+    /// LightGBM only computes training metrics with
+    /// `is_provide_training_metric=true`.
+    pub fn eval_train(&mut self, eval_train: bool) {
+        self.eval_train = eval_train;
+    }
+
+    /// Adds a callback run before and after every iteration, in the order
+    /// callbacks were added.
+    pub fn add_callback(&mut self, callback: impl TrainingCallback + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Boosts with gradients from `objective` instead of LightGBM's built-in
+    /// objective; see [`Objective`].
+    ///
+    /// Fails unless the booster was created with `objective=custom` (or
+    /// `none`): LightGBM would otherwise transform the raw scores of a model
+    /// trained on custom gradients with the built-in objective's link.
+    pub fn set_objective(&mut self, objective: impl Objective + 'static) -> Result<()> {
+        let model = self.booster.save_model_to_string(None)?;
+        // Models of boosters without a built-in objective have no `objective=`
+        // header line.
+        let builtin = model
+            .lines()
+            .take_while(|line| !line.starts_with("Tree="))
+            .find_map(|line| line.strip_prefix("objective="));
+        if let Some(builtin) = builtin {
+            return Err(lgbm::Error::from_message(&format!(
+                "custom objectives need a booster created with `objective=custom`, not `{builtin}`"
+            )));
+        }
+        self.objective = Some(Box::new(objective));
+        Ok(())
+    }
+
+    /// Adds a metric evaluated on every dataset after every iteration, after
+    /// LightGBM's own metrics.
+    pub fn add_metric(&mut self, metric: impl Metric + 'static) -> Result<()> {
+        let name = metric.name();
+        if self.eval_names.iter().any(|n| n == name)
+            || self.metrics.iter().any(|m| m.name() == name)
+        {
+            return Err(lgbm::Error::from_message(&format!(
+                "duplicate metric name `{name}`"
+            )));
+        }
+        self.metrics.push(Box::new(metric));
+        Ok(())
+    }
+
+    /// Changes booster parameters between iterations with
+    /// `LGBM_BoosterResetParameter`, e.g. `learning_rate`. LightGBM rejects
+    /// changes to parameters fixed at construction, such as `num_class`.
+    pub fn reset_parameters(&mut self, parameters: &Parameters) -> Result<()> {
+        self.booster.reset_parameter(parameters)
+    }
+
+    /// Watches a validation metric and stops once it stops improving.
+    pub fn set_early_stopping(&mut self, early_stopping: EarlyStopping) {
+        self.early_stopping = Some(early_stopping);
+    }
+
+    /// Runs one boosting iteration and evaluates every dataset.
+    ///
+    /// Returns why training should stop, if it should.
+    pub fn update(&mut self) -> Result<Option<Stop>> {
+        self.model.take();
+        let iteration = self.current_iteration()? + 1;
+        let start = self.history.evaluations.len();
+        if self.run_callbacks(iteration, start, Hook::Before)? == Control::Stop {
+            return Ok(Some(Stop::Callback));
+        }
+        if self.boost()? {
+            return Ok(Some(Stop::NoSplits));
+        }
+        for data_idx in self.eval_data_indices() {
+            let dataset = self.dataset_name(data_idx).to_string();
+            for (metric, value) in self.eval_names.iter().zip(self.booster.eval(data_idx)?) {
+                self.history.evaluations.push(Evaluation {
+                    iteration,
+                    dataset: dataset.clone(),
+                    metric: metric.clone(),
+                    value,
+                });
+            }
+            self.eval_custom(iteration, data_idx, &dataset)?;
+        }
+
+        let control = self.run_callbacks(iteration, start, Hook::After)?;
+        if let Some(early_stopping) = &mut self.early_stopping
+            && early_stopping.update(iteration, &self.history.evaluations[start..], |name| {
+                self.metrics
+                    .iter()
+                    .find(|m| m.name() == name)
+                    .map_or_else(|| higher_is_better(name), |m| m.higher_is_better())
+            })
+        {
+            let (best_iteration, _) = early_stopping.best().unwrap();
+            if early_stopping.rolls_back() {
+                for _ in best_iteration..iteration {
+                    self.booster.rollback_one_iter()?;
+                }
+            }
+            return Ok(Some(Stop::EarlyStopping { best_iteration }));
+        }
+        Ok((control == Control::Stop).then_some(Stop::Callback))
+    }
+
+    /// One boosting iteration with the built-in or custom objective.
+    fn boost(&mut self) -> Result<bool> {
+        let Some(objective) = &mut self.objective else {
+            return self.booster.update_one_iter();
+        };
+        let scores = self.booster.predictions(0)?;
+        let labels = self.metadata[0].labels().unwrap_or_default();
+        let (grad, hess) = objective.gradients(&scores, labels);
+        check_gradients(scores.len(), &grad, &hess)?;
+        self.booster.update_one_iter_custom(&grad, &hess)
+    }
+
+    /// Evaluates the custom metrics on one dataset.
+    fn eval_custom(&mut self, iteration: usize, data_idx: usize, dataset: &str) -> Result<()> {
+        if self.metrics.is_empty() {
+            return Ok(());
+        }
+        let preds = self.booster.predictions(data_idx)?;
+        let metadata = &self.metadata[data_idx];
+        let labels = metadata.labels().unwrap_or_default();
+        for metric in &mut self.metrics {
+            let value = metric.evaluate(&preds, labels, metadata.weights());
+            self.history.evaluations.push(Evaluation {
+                iteration,
+                dataset: dataset.to_string(),
+                metric: metric.name().to_string(),
+                value,
+            });
+        }
+        Ok(())
+    }
+
+    fn run_callbacks(&mut self, iteration: usize, start: usize, hook: Hook) -> Result<Control> {
+        let ctx = CallbackContext {
+            iteration,
+            booster: &self.booster,
+            evaluations: &self.history.evaluations[start..],
+            history: &self.history,
+            init_model: self.init_model.as_ref(),
+            reset: RefCell::new(Vec::new()),
+        };
+        let mut control = Control::Continue;
+        for callback in &mut self.callbacks {
+            let c = match hook {
+                Hook::Before => callback.before_iteration(&ctx)?,
+                Hook::After => callback.after_iteration(&ctx)?,
+            };
+            if c == Control::Stop {
+                control = Control::Stop;
+            }
+        }
+        for parameters in ctx.reset.into_inner() {
+            self.reset_parameters(&parameters)?;
+        }
+        Ok(control)
+    }
+
+    /// Runs up to `num_iterations` iterations or until [`Trainer::update`]
+    /// asks to stop.
+    pub fn train(&mut self, num_iterations: usize) -> Result<Option<Stop>> {
+        for _ in 0..num_iterations {
+            if let Some(stop) = self.update()? {
+                return Ok(Some(stop));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Best iteration found by early stopping, if enabled.
+    pub fn best_iteration(&self) -> Option<usize> {
+        self.early_stopping
+            .as_ref()
+            .and_then(|e| e.best())
+            .map(|(iteration, _)| iteration)
+    }
+
+    /// The model up to the best iteration (all iterations without early
+    /// stopping) as LightGBM text, including the trees of the init model
+    /// when resuming.
+    pub fn model_to_string(&self) -> Result<String> {
+        let num_iteration = self.best_iteration().map(|i| i - self.iteration_offset());
+        let model = self.booster.save_model_to_string(num_iteration)?;
+        match &self.init_model {
+            Some(init_model) => init_model.merge(&model),
+            None => Ok(model),
+        }
+    }
+
+    /// [`Trainer::model_to_string`] loaded as a [`Booster`] for predictions.
+    fn model(&self) -> Result<&Booster> {
+        if let Some(model) = self.model.get() {
+            return Ok(model);
+        }
+        let model = load_model(&self.model_to_string()?)?;
+        Ok(self.model.get_or_init(|| model))
+    }
+
+    /// Saves the model up to the best iteration (all iterations without early
+    /// stopping).
+    pub fn save_model(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.model_to_string()?).map_err(lgbm::Error::from_error)
+    }
+
+    /// Predicts with the model up to the best iteration (all iterations
+    /// without early stopping).
+    pub fn predict_for_mat<T: FeatureData>(
+        &self,
+        mat: impl AsMat<T>,
+        predict_type: PredictType,
+        parameters: &Parameters,
+    ) -> Result<Prediction> {
+        self.model()?
+            .predict_for_mat(mat, predict_type, 0, None, parameters)
+    }
+
+    /// Like [`Trainer::predict_for_mat`], as a [`Predictions`] matrix.
+    pub fn predict<T: FeatureData>(
+        &self,
+        mat: impl AsMat<T>,
+        predict_type: PredictType,
+        parameters: &Parameters,
+    ) -> Result<Predictions> {
+        let prediction = self.predict_for_mat(mat, predict_type, parameters)?;
+        Predictions::new(&prediction, predict_type)
+    }
+
+    /// SHAP explanations of every row of `mat` with the model up to the best
+    /// iteration; see [`explain`](crate::explain).
+    pub fn explain<T: FeatureData>(
+        &self,
+        mat: impl AsMat<T>,
+        parameters: &Parameters,
+    ) -> Result<Vec<Explanation>> {
+        let predictions = self.predict(mat, PredictType::Contrib, parameters)?;
+        Explanation::from_predictions(&predictions, &self.model()?.get_feature_names()?)
+    }
+
+    fn eval_data_indices(&self) -> impl Iterator<Item = usize> + use<> {
+        let first = if self.eval_train { 0 } else { 1 };
+        first..=self.valid_names.len()
+    }
+
+    /// `training` for index 0, otherwise the validation dataset's name.
+    pub fn dataset_name(&self, data_idx: usize) -> &str {
+        match data_idx {
+            0 => "training",
+            i => &self.valid_names[i - 1],
+        }
+    }
+
+    pub fn valid_names(&self) -> &[String] {
+        &self.valid_names
+    }
+
+    /// Metric names, as reported by LightGBM. Custom metrics are not included.
+    pub fn eval_names(&self) -> &[String] {
+        &self.eval_names
+    }
+
+    pub fn history(&self) -> &EvalHistory {
+        &self.history
+    }
+
+    /// The model up to the best iteration as a [`Booster`], e.g. for
+    /// [`FastPredictor`](crate::FastPredictor). It is loaded from
+    /// [`Trainer::model_to_string`] and does not change with further
+    /// iterations.
+    pub fn booster(&self) -> Result<&Booster> {
+        self.model()
+    }
+
+    pub fn into_booster(mut self) -> Result<Booster> {
+        match self.model.take() {
+            Some(model) => Ok(model),
+            None => load_model(&self.model_to_string()?),
+        }
+    }
+}
+
+/// A dataset of `features` with the fields of `metadata`, which must have
+/// labels.
+fn build_dataset<T: FeatureData>(
+    features: impl AsMat<T>,
+    metadata: &Metadata,
+    reference: Option<&RawDataset>,
+    parameters: &Parameters,
+) -> Result<RawDataset> {
+    if metadata.labels().is_none() {
+        return Err(lgbm::Error::from_message("training datasets need labels"));
+    }
+    let mut dataset = RawDataset::from_mat(features, reference, parameters)?;
+    metadata.apply_raw(&mut dataset)?;
+    Ok(dataset)
+}
+
+fn load_model(model: &str) -> Result<Booster> {
+    let model = CString::new(model).map_err(lgbm::Error::from_error)?;
+    let (booster, _) = Booster::from_string(&model)?;
+    Ok(booster)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two iterations of `l2` and `auc` on `training` and `valid`, in the
+    /// order [`Trainer::update`] records them.
+    fn history() -> EvalHistory {
+        let mut evaluations = Vec::new();
+        for iteration in 1..=2 {
+            for dataset in ["training", "valid"] {
+                for metric in ["l2", "auc"] {
+                    let offset = if dataset == "valid" { 0.5 } else { 0.0 };
+                    let value = match metric {
+                        "l2" => offset + 1.0 / iteration as f64,
+                        _ => offset + 0.25 * iteration as f64,
+                    };
+                    evaluations.push(Evaluation {
+                        iteration,
+                        dataset: dataset.to_string(),
+                        metric: metric.to_string(),
+                        value,
+                    });
+                }
+            }
+        }
+        EvalHistory::from_evaluations(evaluations)
+    }
+
+    #[test]
+    fn values_of_one_metric_on_one_dataset() {
+        let history = history();
+        assert_eq!(history.values("training", "l2"), [1.0, 0.5]);
+        assert_eq!(history.values("valid", "l2"), [1.5, 1.0]);
+        assert_eq!(history.values("valid", "auc"), [0.75, 1.0]);
+        assert!(history.values("valid", "ndcg").is_empty());
+        assert_eq!(history.iteration(2).count(), 4);
+    }
+
+    #[test]
+    fn write_csv_writes_a_row_per_evaluation_in_order() {
+        let mut csv = Vec::new();
+        history().write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "iteration,dataset,metric,value\n\
+             1,training,l2,1\n\
+             1,training,auc,0.25\n\
+             1,valid,l2,1.5\n\
+             1,valid,auc,0.75\n\
+             2,training,l2,0.5\n\
+             2,training,auc,0.5\n\
+             2,valid,l2,1\n\
+             2,valid,auc,1\n"
+        );
+    }
+
+    #[test]
+    fn write_csv_of_empty_history_is_the_header() {
+        let mut csv = Vec::new();
+        EvalHistory::default().write_csv(&mut csv).unwrap();
+        assert_eq!(csv, b"iteration,dataset,metric,value\n");
+    }
+}
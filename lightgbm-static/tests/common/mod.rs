@@ -0,0 +1,38 @@
+//! The training data and parameters of `testapp/main.cpp`, shared by the
+//! integration tests of `lightgbm-static` and `testapp-rs`.
+
+use lgbm::{MatBuf, Parameters, mat::RowMajor};
+
+/// Parameter string passed to `LGBM_DatasetCreateFromMat` and
+/// `LGBM_BoosterCreate` in `testapp/main.cpp`.
+pub const PARAMS: &str = "objective=regression metric=l2 num_leaves=10 learning_rate=0.05 feature_fraction=1.0 bagging_fraction=1.0 min_data_in_leaf=1 min_sum_hessian_in_leaf=1.0 num_threads=0 verbosity=1";
+
+pub const NUM_ITERATIONS: usize = 10;
+
+pub const FEATURES: [[f64; 3]; 5] = [
+    [1.0, 0.5, 0.3],
+    [2.0, 0.6, 0.4],
+    [3.0, 0.7, 0.5],
+    [4.0, 0.8, 0.6],
+    [5.0, 0.9, 0.7],
+];
+
+pub const LABELS: [f32; 5] = [0.1, 0.2, 0.3, 0.4, 0.5];
+
+pub fn parameters() -> Parameters {
+    parse(PARAMS)
+}
+
+/// Parameters from a whitespace-separated `key=value` string.
+pub fn parse(params: &str) -> Parameters {
+    let mut p = Parameters::new();
+    for param in params.split_whitespace() {
+        let (key, value) = param.split_once('=').unwrap();
+        p.push(key, value.to_string());
+    }
+    p
+}
+
+pub fn features() -> MatBuf<f64, RowMajor> {
+    MatBuf::from_rows(FEATURES)
+}
@@ -0,0 +1,282 @@
+//! Training loop behaviour that needs the linked LightGBM.
+//!
+//! Uses the 5×3 matrix of `testapp/main.cpp`: every tree has two leaves
+//! (rows 1–3 | rows 4–5), so predictions move towards the group means by a
+//! step proportional to the learning rate of each iteration.
+
+mod common;
+
+use common::{FEATURES, LABELS, NUM_ITERATIONS, PARAMS, features, parameters, parse};
+use lgbm::{Booster, Dataset, Field, Parameters, PredictType};
+use lightgbm_static::{
+    EarlyStopping, FastPredictor, InitModel, LearningRateSchedule, Metadata, Metric, Stop, Trainer,
+};
+use std::{ffi::CString, sync::Arc};
+
+fn metadata() -> Metadata {
+    let mut metadata = Metadata::new(LABELS.len());
+    metadata.set_labels(LABELS.to_vec()).unwrap();
+    metadata
+}
+
+fn trainer() -> Trainer {
+    Trainer::new(features(), metadata(), &parameters()).unwrap()
+}
+
+fn predict(trainer: &Trainer) -> Vec<f64> {
+    trainer
+        .predict_for_mat(features(), PredictType::Normal, &Parameters::new())
+        .unwrap()
+        .values()
+        .to_vec()
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, b) in actual.iter().zip(expected) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+}
+
+/// Difference between the predictions of the two leaf groups.
+fn spread(predictions: &[f64]) -> f64 {
+    predictions[4] - predictions[0]
+}
+
+#[test]
+fn learning_rate_schedule_changes_tree_output() {
+    let mut constant = trainer();
+    assert_eq!(constant.train(NUM_ITERATIONS).unwrap(), None);
+    let constant = predict(&constant);
+
+    // A schedule that keeps the configured rate must not change anything.
+    let mut same = trainer();
+    same.add_callback(LearningRateSchedule::new(|_| 0.05));
+    same.train(NUM_ITERATIONS).unwrap();
+    for (a, b) in predict(&same).iter().zip(&constant) {
+        assert!((a - b).abs() < 1e-12, "{a} != {b}");
+    }
+
+    let mut decaying = trainer();
+    decaying.add_callback(LearningRateSchedule::new(|i| {
+        0.05 * 0.5f64.powi(i as i32 - 1)
+    }));
+    decaying.train(NUM_ITERATIONS).unwrap();
+    let decaying = predict(&decaying);
+
+    // Smaller steps leave the two groups closer together.
+    assert!(
+        spread(&decaying) < spread(&constant) - 1e-3,
+        "decaying spread {} vs constant {}",
+        spread(&decaying),
+        spread(&constant)
+    );
+    // With a fixed first step, later halving steps sum to less than 2× it.
+    let mut first = trainer();
+    first.train(1).unwrap();
+    assert!(spread(&decaying) < 2.0 * spread(&predict(&first)));
+}
+
+#[test]
+fn reset_parameters_applies_to_later_iterations() {
+    let mut frozen = trainer();
+    frozen.train(1).unwrap();
+    let after_one = predict(&frozen);
+
+    let mut tiny = Parameters::new();
+    tiny.push("learning_rate", 1e-9);
+    frozen.reset_parameters(&tiny).unwrap();
+    frozen.train(5).unwrap();
+    for (a, b) in predict(&frozen).iter().zip(&after_one) {
+        assert!((a - b).abs() < 1e-6, "{a} moved from {b}");
+    }
+}
+
+#[test]
+fn resumed_model_predicts_like_straight_through() {
+    let mut straight = trainer();
+    straight.train(NUM_ITERATIONS).unwrap();
+
+    let mut first = trainer();
+    first.train(4).unwrap();
+    let init_model = InitModel::from_string(first.model_to_string().unwrap()).unwrap();
+    let mut resumed =
+        Trainer::with_init_model(features(), metadata(), &parameters(), init_model).unwrap();
+    resumed.train(NUM_ITERATIONS - 4).unwrap();
+    assert_eq!(resumed.current_iteration().unwrap(), NUM_ITERATIONS);
+    assert_close(&predict(&resumed), &predict(&straight));
+
+    // The saved model holds the trees of both runs.
+    let model = CString::new(resumed.model_to_string().unwrap()).unwrap();
+    let (booster, num_iterations) = Booster::from_string(&model).unwrap();
+    assert_eq!(num_iterations, NUM_ITERATIONS);
+    let reloaded = booster
+        .predict_for_mat(features(), PredictType::Normal, 0, None, &Parameters::new())
+        .unwrap();
+    assert_close(reloaded.values(), &predict(&straight));
+}
+
+#[test]
+fn resumed_validation_scores_start_from_the_init_model() {
+    let mut straight = trainer();
+    straight.add_valid("valid", features(), metadata()).unwrap();
+    straight.train(NUM_ITERATIONS).unwrap();
+
+    let mut first = trainer();
+    first.train(4).unwrap();
+    let init_model = InitModel::from_string(first.model_to_string().unwrap()).unwrap();
+    let mut resumed =
+        Trainer::with_init_model(features(), metadata(), &parameters(), init_model).unwrap();
+    resumed.add_valid("valid", features(), metadata()).unwrap();
+    resumed.train(NUM_ITERATIONS - 4).unwrap();
+    assert_close(
+        &resumed.history().values("valid", "l2"),
+        &straight.history().values("valid", "l2")[4..],
+    );
+}
+
+#[test]
+fn custom_objective_needs_objective_custom() {
+    let squared_error = |scores: &[f64], labels: &[f32]| {
+        scores
+            .iter()
+            .zip(labels)
+            .map(|(&score, &label)| ((score - f64::from(label)) as f32, 1.0))
+            .unzip()
+    };
+    assert!(trainer().set_objective(squared_error).is_err());
+
+    let p = parse(&PARAMS.replace("objective=regression", "objective=custom"));
+    let mut custom = Trainer::new(features(), metadata(), &p).unwrap();
+    custom.set_objective(squared_error).unwrap();
+    assert_eq!(custom.train(NUM_ITERATIONS).unwrap(), None);
+}
+
+/// Weighted mean of the labels.
+struct WeightedLabelMean;
+
+impl Metric for WeightedLabelMean {
+    fn name(&self) -> &str {
+        "weighted_label_mean"
+    }
+
+    fn higher_is_better(&self) -> bool {
+        false
+    }
+
+    fn evaluate(&mut self, _preds: &[f64], labels: &[f32], weights: Option<&[f32]>) -> f64 {
+        let weight = |i: usize| weights.map_or(1.0, |w| f64::from(w[i]));
+        let total = (0..labels.len()).map(weight).sum::<f64>();
+        labels
+            .iter()
+            .enumerate()
+            .map(|(i, &label)| weight(i) * f64::from(label))
+            .sum::<f64>()
+            / total
+    }
+}
+
+/// [`spread`], which grows with every iteration.
+struct Spread {
+    higher_is_better: bool,
+}
+
+impl Metric for Spread {
+    fn name(&self) -> &str {
+        "spread"
+    }
+
+    fn higher_is_better(&self) -> bool {
+        self.higher_is_better
+    }
+
+    fn evaluate(&mut self, preds: &[f64], _labels: &[f32], _weights: Option<&[f32]>) -> f64 {
+        spread(preds)
+    }
+}
+
+#[test]
+fn custom_metrics_see_every_dataset_with_its_weights() {
+    let mut trainer = trainer();
+    trainer.eval_train(true);
+    let mut last_row_only = metadata();
+    last_row_only
+        .set_weights(vec![0.0, 0.0, 0.0, 0.0, 1.0])
+        .unwrap();
+    trainer
+        .add_valid("valid", features(), last_row_only)
+        .unwrap();
+    trainer.add_metric(WeightedLabelMean).unwrap();
+    assert!(trainer.add_metric(WeightedLabelMean).is_err());
+    trainer.train(3).unwrap();
+
+    let history = trainer.history();
+    assert_close(
+        &history.values("training", "weighted_label_mean"),
+        &[0.3; 3],
+    );
+    assert_close(&history.values("valid", "weighted_label_mean"), &[0.5; 3]);
+    // Custom metrics follow LightGBM's own.
+    let metrics = history
+        .iteration(1)
+        .filter(|e| e.dataset == "valid")
+        .map(|e| e.metric.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(metrics, ["l2", "weighted_label_mean"]);
+}
+
+#[test]
+fn custom_metric_direction_drives_early_stopping() {
+    for (higher_is_better, expected) in [
+        (true, None),
+        (false, Some(Stop::EarlyStopping { best_iteration: 1 })),
+    ] {
+        let mut trainer = trainer();
+        trainer.add_valid("valid", features(), metadata()).unwrap();
+        trainer.add_metric(Spread { higher_is_better }).unwrap();
+        trainer.set_early_stopping(EarlyStopping::new(2).metric("spread"));
+        assert_eq!(trainer.train(NUM_ITERATIONS).unwrap(), expected);
+        assert_eq!(
+            trainer.best_iteration(),
+            Some(expected.map_or(NUM_ITERATIONS, |_| 1))
+        );
+    }
+}
+
+#[test]
+fn fast_predictor_matches_predict_for_mat() {
+    let mut train = Dataset::from_mat(features(), None, &parameters()).unwrap();
+    train.set_field(Field::LABEL, &LABELS).unwrap();
+    let mut booster = Booster::new(Arc::new(train), &parameters()).unwrap();
+    for _ in 0..NUM_ITERATIONS {
+        booster.update_one_iter().unwrap();
+    }
+    let features = features();
+    for (predict_type, num_iteration) in [
+        (PredictType::Normal, None),
+        (PredictType::RawScore, Some(3)),
+        (PredictType::Contrib, None),
+    ] {
+        let expected = booster
+            .predict_for_mat(
+                &features,
+                predict_type,
+                0,
+                num_iteration,
+                &Parameters::new(),
+            )
+            .unwrap();
+        let mut predictor =
+            FastPredictor::<f64>::new(&booster, predict_type, 0, num_iteration, &Parameters::new())
+                .unwrap();
+        let num_outputs = predictor.num_outputs();
+        assert_eq!(num_outputs * FEATURES.len(), expected.values().len());
+        for (row, expected) in FEATURES.iter().zip(expected.values().chunks(num_outputs)) {
+            assert_eq!(
+                predictor.predict(row).unwrap(),
+                expected,
+                "{predict_type:?}"
+            );
+        }
+    }
+}
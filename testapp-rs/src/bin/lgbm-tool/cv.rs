@@ -0,0 +1,178 @@
+//! `lgbm-tool cv`: cross-validate parameters on CSV data.
+
+use crate::{
+    data::{Column, CsvArgs, Table, query_keys},
+    params::ParamArgs,
+    train::{DEFAULT_NUM_ITERATIONS, EARLY_STOPPING_ROUND_KEYS, extra},
+};
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use lightgbm_static::{
+    CrossValidation, CvResult, EarlyStopping, Folds, Params, Section, group_sizes,
+};
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+#[derive(Args, Debug)]
+pub struct CvArgs {
+    /// Training data.
+    pub data: PathBuf,
+
+    #[command(flatten)]
+    pub csv: CsvArgs,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+
+    #[command(flatten)]
+    pub cv: CvOptions,
+
+    /// Write the mean and standard deviation of every metric per iteration to
+    /// this CSV file.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// How to split and cross-validate.
+#[derive(Args, Clone, Debug)]
+pub struct CvOptions {
+    /// Number of folds.
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(2..))]
+    pub folds: u64,
+
+    /// Spread every label value evenly over the folds.
+    #[arg(long, conflicts_with_all = ["group_column", "query_column"])]
+    pub stratified: bool,
+
+    /// Keep rows with the same value in this column in one fold. The column
+    /// is not used as a feature.
+    #[arg(long, conflicts_with = "query_column")]
+    pub group_column: Option<Column>,
+
+    /// Query id column for ranking objectives. Rows of a query must be
+    /// consecutive; every query is kept in one fold. The column is not used
+    /// as a feature.
+    #[arg(long)]
+    pub query_column: Option<Column>,
+
+    /// Shuffle rows (or groups) with this seed before splitting.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Train the folds in parallel; consider lowering `num_threads`.
+    #[arg(long)]
+    pub parallel: bool,
+
+    /// Boosting iterations [default: `num_iterations` parameter, or 100].
+    #[arg(long)]
+    pub num_iterations: Option<usize>,
+
+    /// Stop when the mean validation metric has not improved for this many
+    /// iterations [default: `early_stopping_round` parameter, or disabled].
+    #[arg(long)]
+    pub early_stopping_rounds: Option<usize>,
+
+    /// Smallest change that counts as an improvement
+    /// [default: `early_stopping_min_delta` parameter, or 0].
+    #[arg(long)]
+    pub early_stopping_min_delta: Option<f64>,
+
+    /// Metric that picks the best iteration [default: the first metric].
+    #[arg(long)]
+    pub early_stopping_metric: Option<String>,
+}
+
+impl CvOptions {
+    /// Splits `table` into folds, taking the group or query column out of its
+    /// features. Returns the query group sizes with `--query-column`.
+    pub fn folds(&self, table: &mut Table) -> Result<(Folds, Option<Vec<i32>>)> {
+        let num_folds = self.folds as usize;
+        if let Some(column) = &self.query_column {
+            let queries = query_keys(&table.take_column(column)?);
+            let groups = group_sizes(&queries)?;
+            return Ok((
+                Folds::grouped(&queries, num_folds, self.seed)?,
+                Some(groups),
+            ));
+        }
+        let folds = match &self.group_column {
+            Some(column) => {
+                let groups = table.take_column(column)?;
+                Folds::grouped(&query_keys(&groups), num_folds, self.seed)?
+            }
+            None if self.stratified => Folds::stratified(&table.labels, num_folds, self.seed)?,
+            None => Folds::k_fold(table.labels.len(), num_folds, self.seed)?,
+        };
+        Ok((folds, None))
+    }
+
+    /// Cross-validates `params` on `table`, with the query `groups` returned
+    /// by [`CvOptions::folds`].
+    pub fn run(
+        &self,
+        table: &Table,
+        folds: &Folds,
+        groups: Option<&[i32]>,
+        params: &Params,
+    ) -> Result<CvResult> {
+        if params.is_ranking() && groups.is_none() {
+            bail!("ranking objectives need a `--query-column`");
+        }
+        let num_iterations = self
+            .num_iterations
+            .or(params.num_iterations)
+            .unwrap_or(DEFAULT_NUM_ITERATIONS);
+
+        let mut cv =
+            CrossValidation::new(table.features.rows(..), &table.labels).parallel(self.parallel);
+        if let Some(groups) = groups {
+            cv = cv.groups(groups);
+        }
+        let patience = self
+            .early_stopping_rounds
+            .map(Ok)
+            .or_else(|| extra(params, EARLY_STOPPING_ROUND_KEYS))
+            .transpose()?;
+        if patience.is_some() || self.early_stopping_metric.is_some() {
+            // Without a patience the metric only picks the best iteration.
+            let min_delta = match self.early_stopping_min_delta {
+                Some(min_delta) => min_delta,
+                None => extra(params, &["early_stopping_min_delta"]).unwrap_or(Ok(0.0))?,
+            };
+            let mut early_stopping =
+                EarlyStopping::new(patience.unwrap_or(usize::MAX)).min_delta(min_delta);
+            if let Some(metric) = &self.early_stopping_metric {
+                early_stopping = early_stopping.metric(metric);
+            }
+            cv = cv.early_stopping(early_stopping);
+        }
+        Ok(cv.run(folds, &params.to_parameters()?, num_iterations)?)
+    }
+}
+
+pub fn run(args: CvArgs) -> Result<()> {
+    let params = args.params.load(Section::Booster)?.training_params()?;
+    let mut table = Table::read(&args.data, &args.csv)?;
+    let (folds, groups) = args.cv.folds(&mut table)?;
+    let result = args.cv.run(&table, &folds, groups.as_deref(), &params)?;
+
+    for scores in result.scores().chunk_by(|a, b| a.iteration == b.iteration) {
+        eprint!("[{}]", scores[0].iteration);
+        for s in scores {
+            eprint!(" {}: {:.6} ± {:.6}", s.metric, s.mean, s.std);
+        }
+        eprintln!();
+    }
+    eprintln!(
+        "Best iteration: {} ({} {})",
+        result.best_iteration(),
+        result.metric(),
+        result.best_score()
+    );
+
+    if let Some(path) = &args.output {
+        let file =
+            File::create(path).with_context(|| format!("failed to create `{}`", path.display()))?;
+        result.write_csv(BufWriter::new(file))?;
+    }
+    Ok(())
+}
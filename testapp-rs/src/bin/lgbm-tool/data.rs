@@ -0,0 +1,215 @@
+//! Loading delimited text files into LightGBM matrices.
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use lgbm::{MatBuf, mat::RowMajor};
+use lightgbm_static::Metadata;
+use std::{path::Path, str::FromStr};
+
+/// Column selected by zero-based index or by header name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl FromStr for Column {
+    type Err = std::convert::Infallible;
+
+    /// Digits select an index; anything else, or `name:<name>`, selects by name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("name:") {
+            return Ok(Column::Name(name.to_string()));
+        }
+        Ok(match s.parse() {
+            Ok(index) => Column::Index(index),
+            Err(_) => Column::Name(s.to_string()),
+        })
+    }
+}
+
+impl Column {
+    pub fn resolve(&self, names: &[String]) -> Result<usize> {
+        match self {
+            Column::Index(index) if *index < names.len() => Ok(*index),
+            Column::Index(index) => bail!(
+                "column index {index} is out of range ({} columns)",
+                names.len()
+            ),
+            Column::Name(name) => names
+                .iter()
+                .position(|n| n == name)
+                .with_context(|| format!("no column named `{name}`")),
+        }
+    }
+}
+
+/// How to read a delimited text file.
+#[derive(Args, Clone, Debug)]
+pub struct CsvArgs {
+    /// Label column, by zero-based index or header name.
+    #[arg(long, default_value = "0")]
+    pub label: Column,
+
+    /// The first line is data, not column names.
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// Field delimiter.
+    #[arg(long, default_value_t = ',')]
+    pub delimiter: char,
+}
+
+impl CsvArgs {
+    pub fn reader(&self, path: &Path) -> Result<csv::Reader<std::fs::File>> {
+        if !self.delimiter.is_ascii() {
+            bail!("delimiter must be an ASCII character");
+        }
+        csv::ReaderBuilder::new()
+            .delimiter(self.delimiter as u8)
+            .has_headers(!self.no_header)
+            .from_path(path)
+            .with_context(|| format!("failed to open `{}`", path.display()))
+    }
+}
+
+/// Features and labels read from a delimited text file.
+pub struct Table {
+    pub feature_names: Vec<String>,
+    pub features: MatBuf<f64, RowMajor>,
+    pub labels: Vec<f32>,
+    /// Columns of the file, including the label.
+    columns: Vec<String>,
+    /// Column in the file of every feature.
+    feature_columns: Vec<usize>,
+}
+
+impl Table {
+    pub fn read(path: &Path, args: &CsvArgs) -> Result<Self> {
+        let mut reader = args.reader(path)?;
+        let names = column_names(&mut reader)?;
+        let label = args.label.resolve(&names)?;
+        let feature_names = names
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != label)
+            .map(|(_, name)| name.clone())
+            .collect::<Vec<_>>();
+
+        let mut values = Vec::new();
+        let mut labels = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record.with_context(|| format!("failed to read `{}`", path.display()))?;
+            if record.len() != names.len() {
+                bail!(
+                    "{}: row {row} has {} columns, expected {}",
+                    path.display(),
+                    record.len(),
+                    names.len()
+                );
+            }
+            for (col, field) in record.iter().enumerate() {
+                let value = parse_value(field)
+                    .with_context(|| format!("{}: row {row}, column {col}", path.display()))?;
+                if col == label {
+                    if !value.is_finite() {
+                        bail!("{}: row {row} has no label", path.display());
+                    }
+                    labels.push(value as f32);
+                } else {
+                    values.push(value);
+                }
+            }
+        }
+        if labels.is_empty() {
+            bail!("`{}` contains no rows", path.display());
+        }
+        let features = MatBuf::from_vec(values, labels.len(), feature_names.len(), RowMajor);
+        Ok(Self {
+            feature_names,
+            features,
+            labels,
+            feature_columns: (0..names.len()).filter(|&i| i != label).collect(),
+            columns: names,
+        })
+    }
+
+    /// Labels and feature names, plus weights and query groups when their
+    /// columns are given. Those columns are taken out of the features.
+    pub fn metadata(
+        &mut self,
+        weight: Option<&Column>,
+        query: Option<&Column>,
+    ) -> Result<Metadata> {
+        let mut metadata = Metadata::new(self.labels.len());
+        metadata.set_labels(self.labels.clone())?;
+        if let Some(column) = weight {
+            let weights = self.take_column(column)?;
+            metadata.set_weights(weights.into_iter().map(|w| w as f32).collect())?;
+        }
+        if let Some(column) = query {
+            let queries = self.take_column(column)?;
+            metadata.set_query_ids(&query_keys(&queries))?;
+        }
+        metadata.set_feature_names(self.feature_names.clone());
+        Ok(metadata)
+    }
+
+    /// Removes a feature column, e.g. a group or query id, and returns its
+    /// values. `column` is resolved against the columns of the file.
+    pub fn take_column(&mut self, column: &Column) -> Result<Vec<f64>> {
+        let file_index = column.resolve(&self.columns)?;
+        let Some(index) = self.feature_columns.iter().position(|&c| c == file_index) else {
+            bail!("`{}` is not a feature column", self.columns[file_index]);
+        };
+        let ncol = self.feature_names.len();
+        let nrow = self.labels.len();
+        let mut taken = Vec::with_capacity(nrow);
+        let mut values = Vec::with_capacity(nrow * (ncol - 1));
+        for row in 0..nrow {
+            for (col, &value) in self.features.row(row).iter().enumerate() {
+                if col == index {
+                    taken.push(value);
+                } else {
+                    values.push(value);
+                }
+            }
+        }
+        self.feature_names.remove(index);
+        self.feature_columns.remove(index);
+        self.features = MatBuf::from_vec(values, nrow, ncol - 1, RowMajor);
+        Ok(taken)
+    }
+}
+
+/// Numeric ids as hashable keys; all missing values form one id.
+pub fn query_keys(ids: &[f64]) -> Vec<u64> {
+    ids.iter()
+        .map(|id| if id.is_nan() { f64::NAN } else { *id }.to_bits())
+        .collect()
+}
+
+/// Header names, or LightGBM's `Column_<i>` names when there is no header.
+pub fn column_names(reader: &mut csv::Reader<std::fs::File>) -> Result<Vec<String>> {
+    if reader.has_headers() {
+        Ok(reader
+            .headers()?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect())
+    } else {
+        let len = reader.headers()?.len();
+        Ok((0..len).map(|i| format!("Column_{i}")).collect())
+    }
+}
+
+/// Parses a numeric field; empty, `NA` and `NaN` fields are missing values.
+pub fn parse_value(field: &str) -> Result<f64> {
+    let field = field.trim();
+    if field.is_empty() || field.eq_ignore_ascii_case("na") || field.eq_ignore_ascii_case("nan") {
+        return Ok(f64::NAN);
+    }
+    field
+        .parse()
+        .with_context(|| format!("`{field}` is not a number"))
+}
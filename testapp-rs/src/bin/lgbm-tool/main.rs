@@ -0,0 +1,41 @@
+//! Command line front end for the statically linked LightGBM.
+
+mod cv;
+mod data;
+mod params;
+mod predict;
+mod search;
+mod train;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(name = "lgbm-tool", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Train a model from CSV data and save it as a LightGBM text model.
+    Train(train::TrainArgs),
+    /// Cross-validate parameters on CSV data and report the best number of
+    /// iterations.
+    Cv(cv::CvArgs),
+    /// Search hyperparameters by cross-validation and write the best as a
+    /// config file.
+    Search(search::SearchArgs),
+    /// Score a CSV, TSV or LibSVM file with a saved model.
+    Predict(predict::PredictArgs),
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Train(args) => train::run(args),
+        Command::Cv(args) => cv::run(args),
+        Command::Search(args) => search::run(args),
+        Command::Predict(args) => predict::run(args),
+    }
+}
@@ -0,0 +1,46 @@
+//! LightGBM parameters from a config file, `LGBM_TOOL_PARAMS` and `--param`
+//! flags, in increasing order of precedence.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use lightgbm_static::{Config, Section};
+use std::{env, path::PathBuf};
+
+/// Environment variable with whitespace-separated `[section.]key=value` overrides.
+const PARAMS_ENV: &str = "LGBM_TOOL_PARAMS";
+
+#[derive(Args, Clone, Debug)]
+pub struct ParamArgs {
+    /// TOML or YAML config with `[dataset]`, `[booster]` and `[prediction]`
+    /// sections, or a LightGBM config file with one `key = value` per line.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// LightGBM parameter as `[section.]key=value`; overrides the config file
+    /// and `LGBM_TOOL_PARAMS`. Repeatable.
+    #[arg(short, long = "param", value_name = "KEY=VALUE")]
+    pub params: Vec<String>,
+}
+
+impl ParamArgs {
+    /// Loads the config; overrides without a section prefix go to `default`
+    /// unless the parameter is already set in another section.
+    pub fn load(&self, default: Section) -> Result<Config> {
+        let mut config = match &self.config {
+            Some(path) => Config::from_path(path)
+                .with_context(|| format!("invalid config `{}`", path.display()))?,
+            None => Config::default(),
+        };
+        if let Ok(params) = env::var(PARAMS_ENV) {
+            for param in params.split_whitespace() {
+                config
+                    .set(default, param)
+                    .with_context(|| format!("invalid `{PARAMS_ENV}`"))?;
+            }
+        }
+        for param in &self.params {
+            config.set(default, param)?;
+        }
+        Ok(config)
+    }
+}
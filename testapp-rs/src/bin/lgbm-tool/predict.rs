@@ -0,0 +1,517 @@
+//! `lgbm-tool predict`: score a CSV, TSV or LibSVM file with a saved model.
+//!
+//! Rows are read and predicted `--chunk-size` at a time, so memory use does
+//! not grow with the size of the input. Predictions grouped by query are
+//! written as soon as the last row of each query has been read.
+
+use crate::{
+    data::{Column, column_names, parse_value},
+    params::ParamArgs,
+};
+use anyhow::{Context, Result, bail};
+use clap::{Args, ValueEnum};
+use lgbm::{
+    Booster, PredictType,
+    mat::{Mat, RowMajor},
+};
+use lightgbm_static::{LeafIndices, Predictions, Section, group_by_query, tree_num_leaves};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Args, Debug)]
+pub struct PredictArgs {
+    /// Model written by `lgbm-tool train` or LightGBM itself.
+    #[arg(short, long)]
+    pub model: PathBuf,
+
+    /// Data to score.
+    pub data: PathBuf,
+
+    /// Input format [default: from the file extension, otherwise csv].
+    #[arg(long, value_enum)]
+    pub input_format: Option<InputFormat>,
+
+    /// Column to drop from CSV/TSV input, e.g. the label of a training file.
+    #[arg(long)]
+    pub label: Option<Column>,
+
+    /// Query id column of CSV/TSV input. Predictions are grouped by query and
+    /// ranked by score within each; the column is not used as a feature.
+    /// LibSVM input is grouped by its `qid:` tokens when every row has one.
+    /// Rows of a query must be consecutive.
+    #[arg(long)]
+    pub query_column: Option<Column>,
+
+    /// The first line of CSV/TSV input is data, not column names.
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// What to predict.
+    #[arg(long = "type", value_enum, default_value_t = Kind::Normal)]
+    pub kind: Kind,
+
+    /// Rows predicted per call into LightGBM.
+    #[arg(long, default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(1..))]
+    pub chunk_size: u64,
+
+    /// First iteration used for prediction.
+    #[arg(long, default_value_t = 0)]
+    pub start_iteration: usize,
+
+    /// Iterations used for prediction [default: all].
+    #[arg(long)]
+    pub num_iterations: Option<usize>,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+
+    /// Where to write predictions [default: stdout].
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Output format [default: from the output extension, otherwise csv].
+    #[arg(long, value_enum)]
+    pub output_format: Option<OutputFormat>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Csv,
+    Tsv,
+    /// `<label> <index>:<value> ...` with zero-based feature indices.
+    Libsvm,
+}
+
+impl InputFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("tsv" | "tab") => InputFormat::Tsv,
+            Some("svm" | "libsvm") => InputFormat::Libsvm,
+            _ => InputFormat::Csv,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Csv,
+    /// One JSON object per row.
+    Jsonl,
+    /// One-hot encoded leaves as zero-based `<index>:1` pairs, one row per
+    /// line; only for `--type leaf-index`.
+    Libsvm,
+}
+
+impl OutputFormat {
+    fn from_path(path: Option<&Path>) -> Self {
+        match path.and_then(|p| p.extension()).and_then(|e| e.to_str()) {
+            Some("jsonl" | "ndjson") => OutputFormat::Jsonl,
+            Some("svm" | "libsvm") => OutputFormat::Libsvm,
+            _ => OutputFormat::Csv,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// Transformed score, e.g. probabilities for classification.
+    Normal,
+    /// Untransformed score.
+    RawScore,
+    /// Index of the leaf reached in every tree.
+    LeafIndex,
+    /// SHAP feature contributions followed by the expected value.
+    Contrib,
+}
+
+impl From<Kind> for PredictType {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Normal => PredictType::Normal,
+            Kind::RawScore => PredictType::RawScore,
+            Kind::LeafIndex => PredictType::LeafIndex,
+            Kind::Contrib => PredictType::Contrib,
+        }
+    }
+}
+
+pub fn run(args: PredictArgs) -> Result<()> {
+    let p = args
+        .params
+        .load(Section::Prediction)?
+        .prediction_parameters()?;
+    let (booster, _) = Booster::from_file(&args.model)
+        .with_context(|| format!("failed to load model `{}`", args.model.display()))?;
+    let num_feature = booster.get_num_feature()?;
+    let num_class = booster.get_num_classes()?;
+    let feature_names = booster.get_feature_names()?;
+
+    let input_format = args
+        .input_format
+        .unwrap_or_else(|| InputFormat::from_path(&args.data));
+    let mut rows = Rows::open(&args.data, input_format, &args, num_feature)?;
+
+    let output_format = args
+        .output_format
+        .unwrap_or_else(|| OutputFormat::from_path(args.output.as_deref()));
+    let num_leaves = match (output_format, args.kind) {
+        (OutputFormat::Libsvm, Kind::LeafIndex) => tree_num_leaves(&booster)?,
+        (OutputFormat::Libsvm, _) => bail!("libsvm output needs `--type leaf-index`"),
+        _ => Vec::new(),
+    };
+    let first_tree = args.start_iteration * booster.num_model_per_iteration()?;
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("failed to create `{}`", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(out);
+
+    let chunk_size = args.chunk_size as usize;
+    let mut values = Vec::with_capacity(chunk_size * num_feature);
+    let mut queries = Vec::new();
+    let mut groups = None;
+    let mut columns = None;
+    let mut row = 0;
+    loop {
+        values.clear();
+        queries.clear();
+        let nrow = rows.read_chunk(chunk_size, &mut values, &mut queries)?;
+        if nrow == 0 {
+            break;
+        }
+        let predictions = Predictions::predict(
+            &booster,
+            Mat::from_slice(&values, nrow, num_feature, RowMajor),
+            args.kind.into(),
+            args.start_iteration,
+            args.num_iterations,
+            &p,
+        )?;
+        let per_row = predictions.num_cols();
+        if output_format == OutputFormat::Libsvm {
+            if !queries.is_empty() {
+                bail!("grouping by query needs a single score per row");
+            }
+            LeafIndices::from_predictions(&predictions, first_tree)?
+                .one_hot(&num_leaves)?
+                .write_libsvm(&mut out, None)?;
+            row += nrow;
+            continue;
+        }
+        if !queries.is_empty() || groups.is_some() {
+            // Checked for every chunk before any of it is written.
+            if queries.len() != nrow || (row > 0 && groups.is_none()) {
+                bail!("{}: only some rows have a query id", args.data.display());
+            }
+            if !matches!(args.kind, Kind::Normal | Kind::RawScore) || per_row != 1 {
+                bail!("grouping by query needs a single score per row");
+            }
+            if row == 0 && output_format == OutputFormat::Csv {
+                writeln!(out, "query,row,rank,prediction")?;
+            }
+            let groups = groups.get_or_insert_with(QueryGroups::default);
+            groups
+                .push(&queries, predictions.values())
+                .with_context(|| format!("failed to group `{}` by query", args.data.display()))?;
+            groups.write(&mut out, output_format, false)?;
+            row += nrow;
+            continue;
+        }
+        let columns = columns
+            .get_or_insert_with(|| column_names_for(args.kind, num_class, &feature_names, per_row));
+        if row == 0 && output_format == OutputFormat::Csv {
+            writeln!(out, "{}", columns.join(","))?;
+        }
+
+        for values in predictions.rows() {
+            match output_format {
+                OutputFormat::Csv => {
+                    let line = values.iter().map(f64::to_string).collect::<Vec<_>>();
+                    writeln!(out, "{}", line.join(","))?;
+                }
+                OutputFormat::Libsvm => unreachable!("written per chunk"),
+                OutputFormat::Jsonl => {
+                    let mut object = serde_json::Map::new();
+                    object.insert("row".into(), row.into());
+                    for (name, &value) in columns.iter().zip(values) {
+                        object.insert(name.clone(), value.into());
+                    }
+                    writeln!(out, "{}", serde_json::Value::Object(object))?;
+                }
+            }
+            row += 1;
+        }
+    }
+    if let Some(groups) = &mut groups {
+        groups.write(&mut out, output_format, true)?;
+    }
+    out.flush()?;
+
+    if let Some(path) = &args.output {
+        eprintln!("{row} predictions written to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Scores of the queries read so far that have not been written yet.
+#[derive(Default)]
+struct QueryGroups {
+    /// Every query id read, to catch queries whose rows are not consecutive.
+    seen: HashSet<String>,
+    queries: Vec<String>,
+    scores: Vec<f64>,
+    /// Input row of `queries[0]`.
+    first_row: usize,
+}
+
+impl QueryGroups {
+    /// Adds the next rows. Fails when a query id appears again after rows of
+    /// other queries.
+    fn push(&mut self, queries: &[String], scores: &[f64]) -> Result<()> {
+        let mut last = self.queries.last();
+        for run in queries.chunk_by(|a, b| a == b) {
+            let query = &run[0];
+            if last != Some(query) && !self.seen.insert(query.clone()) {
+                bail!("rows of query `{query}` are not consecutive");
+            }
+            last = Some(query);
+        }
+        self.queries.extend_from_slice(queries);
+        self.scores.extend_from_slice(scores);
+        Ok(())
+    }
+
+    /// Writes every complete query, best first, as CSV rows of
+    /// `query,row,rank,prediction` or one JSON object per query. The last
+    /// query may continue in the next rows and is kept unless `end`.
+    fn write(&mut self, out: &mut impl Write, format: OutputFormat, end: bool) -> Result<()> {
+        let complete = match self.queries.chunk_by(|a, b| a == b).next_back() {
+            Some(last) if !end => self.queries.len() - last.len(),
+            _ => self.queries.len(),
+        };
+        let ranked = group_by_query(&self.queries[..complete], &self.scores[..complete])?;
+        for ranked in ranked {
+            let rows = ranked.rows.iter().map(|r| self.first_row + r);
+            match format {
+                OutputFormat::Csv => {
+                    for (rank, (row, score)) in rows.zip(&ranked.scores).enumerate() {
+                        writeln!(out, "{},{row},{},{score}", ranked.query, rank + 1)?;
+                    }
+                }
+                OutputFormat::Libsvm => unreachable!("not a score output"),
+                OutputFormat::Jsonl => {
+                    let object = serde_json::json!({
+                        "query": ranked.query,
+                        "rows": rows.collect::<Vec<_>>(),
+                        "predictions": ranked.scores,
+                    });
+                    writeln!(out, "{object}")?;
+                }
+            }
+        }
+        self.queries.drain(..complete);
+        self.scores.drain(..complete);
+        self.first_row += complete;
+        Ok(())
+    }
+}
+
+/// Output column names for `per_row` values of the given kind.
+fn column_names_for(
+    kind: Kind,
+    num_class: usize,
+    feature_names: &[String],
+    per_row: usize,
+) -> Vec<String> {
+    match kind {
+        Kind::Normal | Kind::RawScore if per_row == 1 => vec!["prediction".to_string()],
+        Kind::Normal | Kind::RawScore => (0..per_row).map(|k| format!("class_{k}")).collect(),
+        Kind::LeafIndex => (0..per_row).map(|i| format!("tree_{i}")).collect(),
+        Kind::Contrib => {
+            let names = feature_names
+                .iter()
+                .map(String::as_str)
+                .chain(["expected_value"])
+                .collect::<Vec<_>>();
+            if num_class <= 1 {
+                names.iter().map(|n| n.to_string()).collect()
+            } else {
+                (0..num_class)
+                    .flat_map(|k| names.iter().map(move |n| format!("class_{k}_{n}")))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Row-by-row reader over the supported input formats.
+enum Rows {
+    Delimited {
+        reader: csv::Reader<File>,
+        skip: Option<usize>,
+        query: Option<usize>,
+        path: PathBuf,
+        row: usize,
+    },
+    Libsvm {
+        lines: io::Lines<BufReader<File>>,
+        num_feature: usize,
+        path: PathBuf,
+        row: usize,
+    },
+}
+
+impl Rows {
+    fn open(
+        path: &Path,
+        format: InputFormat,
+        args: &PredictArgs,
+        num_feature: usize,
+    ) -> Result<Self> {
+        let open_error = || format!("failed to open `{}`", path.display());
+        let delimiter = match format {
+            InputFormat::Csv => b',',
+            InputFormat::Tsv => b'\t',
+            InputFormat::Libsvm => {
+                let file = File::open(path).with_context(open_error)?;
+                return Ok(Rows::Libsvm {
+                    lines: BufReader::new(file).lines(),
+                    num_feature,
+                    path: path.to_path_buf(),
+                    row: 0,
+                });
+            }
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(!args.no_header)
+            .from_path(path)
+            .with_context(open_error)?;
+        let names = column_names(&mut reader)?;
+        let skip = args
+            .label
+            .as_ref()
+            .map(|label| label.resolve(&names))
+            .transpose()?;
+        let query = args
+            .query_column
+            .as_ref()
+            .map(|query| query.resolve(&names))
+            .transpose()?;
+        if query.is_some() && query == skip {
+            bail!("the query column cannot also be the label column");
+        }
+        let columns = names.len() - usize::from(skip.is_some()) - usize::from(query.is_some());
+        if columns != num_feature {
+            bail!(
+                "`{}` has {columns} feature columns, but the model expects {num_feature}",
+                path.display()
+            );
+        }
+        Ok(Rows::Delimited {
+            reader,
+            skip,
+            query,
+            path: path.to_path_buf(),
+            row: 0,
+        })
+    }
+
+    /// Appends up to `max_rows` rows to `values` and their query ids, if
+    /// any, to `queries`, returning how many rows were read.
+    fn read_chunk(
+        &mut self,
+        max_rows: usize,
+        values: &mut Vec<f64>,
+        queries: &mut Vec<String>,
+    ) -> Result<usize> {
+        let mut nrow = 0;
+        while nrow < max_rows && self.read_row(values, queries)? {
+            nrow += 1;
+        }
+        Ok(nrow)
+    }
+
+    fn read_row(&mut self, values: &mut Vec<f64>, queries: &mut Vec<String>) -> Result<bool> {
+        match self {
+            Rows::Delimited {
+                reader,
+                skip,
+                query,
+                path,
+                row,
+            } => {
+                let mut record = csv::StringRecord::new();
+                if !reader
+                    .read_record(&mut record)
+                    .with_context(|| format!("failed to read `{}`", path.display()))?
+                {
+                    return Ok(false);
+                }
+                for (col, field) in record.iter().enumerate() {
+                    if Some(col) == *skip {
+                        continue;
+                    }
+                    if Some(col) == *query {
+                        queries.push(field.trim().to_string());
+                        continue;
+                    }
+                    let value = parse_value(field)
+                        .with_context(|| format!("{}: row {row}, column {col}", path.display()))?;
+                    values.push(value);
+                }
+                *row += 1;
+                Ok(true)
+            }
+            Rows::Libsvm {
+                lines,
+                num_feature,
+                path,
+                row,
+            } => {
+                let line = loop {
+                    match lines.next() {
+                        None => return Ok(false),
+                        Some(line) => {
+                            let line = line
+                                .with_context(|| format!("failed to read `{}`", path.display()))?;
+                            if !line.trim().is_empty() {
+                                break line;
+                            }
+                        }
+                    }
+                };
+                // LightGBM treats features missing from a LibSVM row as zero.
+                let start = values.len();
+                values.resize(start + *num_feature, 0.0);
+                for token in line.split_whitespace() {
+                    // The leading label, if any, has no `:`.
+                    let Some((index, value)) = token.split_once(':') else {
+                        continue;
+                    };
+                    if index == "qid" {
+                        queries.push(value.to_string());
+                        continue;
+                    }
+                    let context = || format!("{}: row {row}, `{token}`", path.display());
+                    let index: usize = index.parse().with_context(context)?;
+                    if index >= *num_feature {
+                        bail!(
+                            "{}: row {row} has feature index {index}, but the model has {num_feature} features",
+                            path.display()
+                        );
+                    }
+                    values[start + index] = parse_value(value).with_context(context)?;
+                }
+                *row += 1;
+                Ok(true)
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! `lgbm-tool search`: cross-validated hyperparameter search.
+
+use crate::{
+    cv::CvOptions,
+    data::{CsvArgs, Table},
+    params::ParamArgs,
+    train::DEFAULT_NUM_ITERATIONS,
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use lightgbm_static::{Search, SearchSpace, Section, Strategy, Trial};
+use std::{fs, path::PathBuf};
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Training data.
+    pub data: PathBuf,
+
+    #[command(flatten)]
+    pub csv: CsvArgs,
+
+    /// Base parameters every candidate starts from.
+    #[command(flatten)]
+    pub params: ParamArgs,
+
+    #[command(flatten)]
+    pub cv: CvOptions,
+
+    /// Parameter to search as `key=<distribution>`: `int:<min>:<max>`,
+    /// `float:<min>:<max>` (append `:log` for log-uniform) or
+    /// `choice:<a>,<b>,...`. Repeatable.
+    #[arg(long = "space", value_name = "KEY=DIST", required = true)]
+    pub space: Vec<String>,
+
+    /// Number of candidates to sample.
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..))]
+    pub trials: u64,
+
+    /// Use successive halving instead of random search: start every
+    /// candidate with `--min-iterations` and keep the best `1/eta` per round.
+    #[arg(long)]
+    pub halving: bool,
+
+    /// Iterations of the first successive halving round.
+    #[arg(long, default_value_t = 10, requires = "halving")]
+    pub min_iterations: usize,
+
+    /// Successive halving reduction factor.
+    #[arg(long, default_value_t = 3, requires = "halving", value_parser = clap::value_parser!(u64).range(2..))]
+    pub eta: u64,
+
+    /// Seed for sampling candidates.
+    #[arg(long, default_value_t = 0)]
+    pub search_seed: u64,
+
+    /// Write every trial to this CSV file as it completes.
+    #[arg(long, default_value = "trials.csv")]
+    pub results: PathBuf,
+
+    /// Where to write the best parameters as a TOML config for
+    /// `lgbm-tool train --config`.
+    #[arg(short, long, default_value = "best.toml")]
+    pub output: PathBuf,
+}
+
+pub fn run(args: SearchArgs) -> Result<()> {
+    let mut config = args.params.load(Section::Booster)?;
+    let base = config.training_params()?;
+    let mut space = SearchSpace::new();
+    for param in &args.space {
+        space.add_str(param)?;
+    }
+    let num_iterations = args
+        .cv
+        .num_iterations
+        .or(base.num_iterations)
+        .unwrap_or(DEFAULT_NUM_ITERATIONS);
+    let strategy = if args.halving {
+        Strategy::SuccessiveHalving {
+            trials: args.trials as usize,
+            min_iterations: args.min_iterations,
+            eta: args.eta as usize,
+        }
+    } else {
+        Strategy::Random {
+            trials: args.trials as usize,
+        }
+    };
+
+    let mut table = Table::read(&args.data, &args.csv)?;
+    let (folds, groups) = args.cv.folds(&mut table)?;
+    // Candidates carry their iteration budget as `num_iterations`.
+    let cv = CvOptions {
+        num_iterations: None,
+        ..args.cv.clone()
+    };
+
+    let keys = space.keys().map(str::to_string).collect::<Vec<_>>();
+    let mut results = csv::Writer::from_path(&args.results)
+        .with_context(|| format!("failed to create `{}`", args.results.display()))?;
+    let mut header = [
+        "trial",
+        "rung",
+        "num_iterations",
+        "best_iteration",
+        "metric",
+        "score",
+    ]
+    .map(String::from)
+    .to_vec();
+    header.extend(keys.iter().cloned());
+    results.write_record(&header)?;
+    results.flush()?;
+
+    let search = Search::new(space, strategy).seed(args.search_seed);
+    let result = search.run(
+        &base,
+        num_iterations,
+        |params| {
+            cv.run(&table, &folds, groups.as_deref(), params)
+                .map_err(|e| lgbm::Error::from_message(&format!("{e:#}")))
+        },
+        |trial: &Trial| {
+            eprintln!(
+                "[trial {} rung {}] {} {} at iteration {}/{}: {}",
+                trial.id,
+                trial.rung,
+                trial.metric,
+                trial.score,
+                trial.best_iteration,
+                trial.num_iterations,
+                trial
+                    .params
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+            let mut record = vec![
+                trial.id.to_string(),
+                trial.rung.to_string(),
+                trial.num_iterations.to_string(),
+                trial.best_iteration.to_string(),
+                trial.metric.clone(),
+                trial.score.to_string(),
+            ];
+            record.extend(trial.params.iter().map(|(_, v)| v.clone()));
+            results
+                .write_record(&record)
+                .and_then(|()| Ok(results.flush()?))
+                .map_err(lgbm::Error::from_error)
+        },
+    )?;
+
+    let best = result.best();
+    // Searched dataset parameters move to `[booster]` with the rest, which
+    // `training_params` would otherwise reject as set twice.
+    for key in &keys {
+        config.dataset.remove(key);
+    }
+    config.booster = result.best_params(&config.booster)?;
+    fs::write(&args.output, config.to_toml_string())
+        .with_context(|| format!("failed to write `{}`", args.output.display()))?;
+    eprintln!(
+        "Best trial {}: {} {} at iteration {}; parameters written to {}",
+        best.id,
+        best.metric,
+        best.score,
+        best.best_iteration,
+        args.output.display()
+    );
+    Ok(())
+}
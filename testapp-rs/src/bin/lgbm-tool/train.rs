@@ -0,0 +1,230 @@
+//! `lgbm-tool train`: train a model from CSV data and save it as text.
+
+use crate::{
+    data::{Column, CsvArgs, Table},
+    params::ParamArgs,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Args;
+use lightgbm_static::{
+    CallbackContext, Checkpoint, Control, EarlyStopping, InitModel, Params, Section, Stop, Trainer,
+};
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub const DEFAULT_NUM_ITERATIONS: usize = 100;
+
+/// Aliases of LightGBM's `early_stopping_round`, which only LightGBM's own
+/// CLI acts on.
+pub const EARLY_STOPPING_ROUND_KEYS: &[&str] = &[
+    "early_stopping_round",
+    "early_stopping_rounds",
+    "early_stopping",
+    "n_iter_no_change",
+];
+
+#[derive(Args, Debug)]
+pub struct TrainArgs {
+    /// Training data.
+    pub data: PathBuf,
+
+    /// Validation data with the same columns as the training data. Repeatable.
+    #[arg(long)]
+    pub valid: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub csv: CsvArgs,
+
+    /// Row weight column, by zero-based index or header name. The column is
+    /// not used as a feature.
+    #[arg(long)]
+    pub weight_column: Option<Column>,
+
+    /// Query id column for ranking objectives, by zero-based index or header
+    /// name. Rows of a query must be consecutive. The column is not used as a
+    /// feature. Validation queries are scored with e.g.
+    /// `-p metric=ndcg,map -p eval_at=1,3,5`.
+    #[arg(long)]
+    pub query_column: Option<Column>,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+
+    /// Boosting iterations [default: `num_iterations` parameter, or 100].
+    #[arg(long)]
+    pub num_iterations: Option<usize>,
+
+    /// Stop when the watched validation metric has not improved for this many
+    /// iterations [default: `early_stopping_round` parameter, or disabled].
+    #[arg(long)]
+    pub early_stopping_rounds: Option<usize>,
+
+    /// Smallest change that counts as an improvement
+    /// [default: `early_stopping_min_delta` parameter, or 0].
+    #[arg(long)]
+    pub early_stopping_min_delta: Option<f64>,
+
+    /// Metric watched by early stopping [default: the first metric].
+    #[arg(long)]
+    pub early_stopping_metric: Option<String>,
+
+    /// Validation dataset watched by early stopping, e.g. `valid_2`
+    /// [default: the first one].
+    #[arg(long)]
+    pub early_stopping_dataset: Option<String>,
+
+    /// Write a model snapshot to this directory every `--checkpoint-every`
+    /// iterations.
+    #[arg(long)]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// Iterations between snapshots.
+    #[arg(long, default_value_t = 10)]
+    pub checkpoint_every: usize,
+
+    /// Keep only this many of the newest snapshots.
+    #[arg(long)]
+    pub keep_checkpoints: Option<usize>,
+
+    /// Continue training from a saved model, or from the newest snapshot in a
+    /// checkpoint directory. `--num-iterations` counts the resumed iterations.
+    #[arg(long)]
+    pub resume: Option<PathBuf>,
+
+    /// Write every validation metric of every iteration to this CSV file.
+    #[arg(long)]
+    pub history: Option<PathBuf>,
+
+    /// Where to write the model.
+    #[arg(short, long, default_value = "model.txt")]
+    pub output: PathBuf,
+}
+
+pub fn run(args: TrainArgs) -> Result<()> {
+    let params = args.params.load(Section::Booster)?.training_params()?;
+    let p = params.to_parameters()?;
+    let num_iterations = args
+        .num_iterations
+        .or(params.num_iterations)
+        .unwrap_or(DEFAULT_NUM_ITERATIONS);
+
+    if params.is_ranking() && args.query_column.is_none() {
+        bail!("ranking objectives need a `--query-column`");
+    }
+
+    let init_model = match &args.resume {
+        Some(path) => Some(init_model(path)?),
+        None => None,
+    };
+
+    let mut table = Table::read(&args.data, &args.csv)?;
+    let metadata = table.metadata(args.weight_column.as_ref(), args.query_column.as_ref())?;
+    let mut trainer = match init_model {
+        Some(init_model) => {
+            eprintln!("Resuming after iteration {}", init_model.num_iterations());
+            Trainer::with_init_model(&table.features, metadata, &p, init_model)?
+        }
+        None => Trainer::new(&table.features, metadata, &p)?,
+    };
+    for (i, path) in args.valid.iter().enumerate() {
+        let mut valid_table = Table::read(path, &args.csv)?;
+        let metadata =
+            valid_table.metadata(args.weight_column.as_ref(), args.query_column.as_ref())?;
+        if valid_table.feature_names.len() != table.feature_names.len() {
+            bail!(
+                "`{}` has {} features, but the training data has {}",
+                path.display(),
+                valid_table.feature_names.len(),
+                table.feature_names.len()
+            );
+        }
+        trainer.add_valid(format!("valid_{}", i + 1), &valid_table.features, metadata)?;
+    }
+
+    if let Some(patience) = args
+        .early_stopping_rounds
+        .map(Ok)
+        .or_else(|| extra(&params, EARLY_STOPPING_ROUND_KEYS))
+        .transpose()?
+    {
+        if args.valid.is_empty() {
+            bail!("early stopping requires at least one `--valid` dataset");
+        }
+        let min_delta = match args.early_stopping_min_delta {
+            Some(min_delta) => min_delta,
+            None => extra(&params, &["early_stopping_min_delta"]).unwrap_or(Ok(0.0))?,
+        };
+        let mut early_stopping = EarlyStopping::new(patience).min_delta(min_delta);
+        if let Some(metric) = &args.early_stopping_metric {
+            early_stopping = early_stopping.metric(metric);
+        }
+        if let Some(dataset) = &args.early_stopping_dataset {
+            early_stopping = early_stopping.dataset(dataset);
+        }
+        trainer.set_early_stopping(early_stopping);
+    }
+
+    if let Some(dir) = &args.checkpoint_dir {
+        let mut checkpoint = Checkpoint::new(dir, args.checkpoint_every);
+        if let Some(keep) = args.keep_checkpoints {
+            checkpoint = checkpoint.keep_last(keep);
+        }
+        trainer.add_callback(checkpoint);
+    }
+
+    trainer.add_callback(|ctx: &CallbackContext<'_>| {
+        for e in ctx.evaluations {
+            eprintln!("[{}] {} {}: {}", e.iteration, e.dataset, e.metric, e.value);
+        }
+        Ok(Control::Continue)
+    });
+    let remaining = num_iterations.saturating_sub(trainer.current_iteration()?);
+    match trainer.train(remaining)? {
+        Some(Stop::NoSplits) => eprintln!(
+            "Stopped after {} iterations: no further splits",
+            trainer.current_iteration()?
+        ),
+        Some(Stop::EarlyStopping { best_iteration }) => eprintln!(
+            "Early stopping after {} iterations, best iteration: {best_iteration}",
+            trainer.current_iteration()?
+        ),
+        Some(Stop::Callback) | None => {}
+    }
+
+    if let Some(path) = &args.history {
+        let file =
+            File::create(path).with_context(|| format!("failed to create `{}`", path.display()))?;
+        trainer.history().write_csv(BufWriter::new(file))?;
+    }
+    trainer.save_model(&args.output)?;
+    eprintln!("Model written to {}", args.output.display());
+    Ok(())
+}
+
+/// Parses the first of `keys` set as an untyped parameter.
+pub fn extra<T: FromStr>(params: &Params, keys: &[&str]) -> Option<Result<T>> {
+    let (key, value) = params
+        .extra
+        .iter()
+        .find(|(k, _)| keys.contains(&k.as_str()))?;
+    Some(
+        value
+            .parse()
+            .map_err(|_| anyhow!("invalid value `{value}` for `{key}`")),
+    )
+}
+
+/// Loads `path`, or the newest snapshot in it if it is a directory.
+fn init_model(path: &Path) -> Result<InitModel> {
+    let path = if path.is_dir() {
+        Checkpoint::latest(path)?
+            .with_context(|| format!("no snapshots in `{}`", path.display()))?
+    } else {
+        path.to_path_buf()
+    };
+    InitModel::from_file(&path).with_context(|| format!("failed to load `{}`", path.display()))
+}
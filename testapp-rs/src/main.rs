@@ -1,19 +1,93 @@
 use lgbm::{
-    Booster, Dataset, Field, MatBuf, Parameters,
+    Booster, Dataset, FeatureImportanceType, Field, MatBuf, Parameters, PredictType,
+    parameters::{Metric, Objective, Verbosity},
 };
+use lightgbm_static::{BuildInfo, Params};
 use std::sync::Arc;
 
+const NUM_ITERATIONS: usize = 10;
+
 fn main() {
-    let p = Parameters::new();
-    let mut train = Dataset::from_mat(&MatBuf::from_rows(train_features()), None, &p).unwrap();
-    train.set_field(Field::LABEL, &train_labels()).unwrap();
-    let _b = Booster::new(Arc::new(train), &p).unwrap();
+    println!("LightGBM Static Library Test (Rust)");
+    println!("===================================");
+    println!();
+
+    println!("{}", BuildInfo::current());
+    println!();
+
+    let p = parameters();
+    println!("Training Configuration:");
+    println!("- {p}");
+    println!();
+
+    let features = MatBuf::from_rows(train_features());
+    let labels = train_labels();
+    let mut train = Dataset::from_mat(&features, None, &p).unwrap();
+    train.set_field(Field::LABEL, &labels).unwrap();
+    let mut b = Booster::new(Arc::new(train), &p).unwrap();
+
+    for i in 0..NUM_ITERATIONS {
+        if b.update_one_iter().unwrap() {
+            println!("Early stopping at iteration {i}");
+            break;
+        }
+    }
+    println!("Training completed successfully!");
+
+    let predictions = b
+        .predict_for_mat(&features, PredictType::Normal, 0, None, &Parameters::new())
+        .unwrap();
+    println!();
+    println!("Predictions:");
+    for (i, label) in labels.iter().enumerate() {
+        println!(
+            "  Sample {}: Actual = {label}, Predicted = {}",
+            i + 1,
+            predictions[i]
+        );
+    }
+
+    let importance = b
+        .feature_importance(None, FeatureImportanceType::Split)
+        .unwrap();
+    println!();
+    println!("Feature Importance (splits):");
+    for (i, value) in importance.iter().enumerate() {
+        println!("  Feature {i}: {value}");
+    }
+
+    println!();
+    println!("LightGBM static library successfully integrated!");
+}
+
+/// Same parameter set as `testapp/main.cpp`.
+fn parameters() -> Parameters {
+    Params::builder()
+        .objective(Objective::Regression)
+        .metric(Metric::L2)
+        .num_leaves(10)
+        .learning_rate(0.05)
+        .feature_fraction(1.0)
+        .bagging_fraction(1.0)
+        .min_data_in_leaf(1)
+        .min_sum_hessian_in_leaf(1.0)
+        .num_threads(0)
+        .verbosity(Verbosity::Info)
+        .build()
+        .and_then(|params| params.to_parameters())
+        .unwrap()
 }
 
-fn train_features() -> Vec<[f64; 1]> {
-    (0..128).map(|x| [(x % 3) as f64]).collect()
+fn train_features() -> Vec<[f64; 3]> {
+    vec![
+        [1.0, 0.5, 0.3],
+        [2.0, 0.6, 0.4],
+        [3.0, 0.7, 0.5],
+        [4.0, 0.8, 0.6],
+        [5.0, 0.9, 0.7],
+    ]
 }
 
 fn train_labels() -> Vec<f32> {
-    (0..128).map(|x| (x % 3) as f32).collect()
+    vec![0.1, 0.2, 0.3, 0.4, 0.5]
 }
@@ -0,0 +1,73 @@
+//! Golden parity check against `testapp/main.cpp`.
+//!
+//! Trains on the same 5×3 matrix with the same parameter string as the C++
+//! test app and compares predictions and split importances with known-good
+//! values. A failure here means the linked `lib_lightgbm.a` no longer behaves
+//! like the one both apps were written against.
+//!
+//! With LightGBM's default `min_data_in_bin=3` every feature gets a single
+//! usable threshold (rows 1–3 | rows 4–5), so each tree has two leaves and the
+//! expected scores follow in closed form: the label mean plus the group mean
+//! residual times `1 - (1 - learning_rate)^10`. All three features tie on
+//! gain and LightGBM breaks ties towards the lowest feature index.
+
+#[path = "../../lightgbm-static/tests/common/mod.rs"]
+mod common;
+
+use common::{LABELS, NUM_ITERATIONS, features, parameters};
+use lgbm::{Booster, Dataset, FeatureImportanceType, Field, Parameters, PredictType};
+use std::sync::Arc;
+
+const GOLDEN_PREDICTIONS: [f64; 5] = [
+    0.259_873_698_792_805,
+    0.259_873_698_792_805,
+    0.259_873_698_792_805,
+    0.360_189_462_986_663,
+    0.360_189_462_986_663,
+];
+
+const GOLDEN_SPLIT_IMPORTANCE: [f64; 3] = [10.0, 0.0, 0.0];
+
+/// LightGBM accumulates gradients and scores in `f32`.
+const TOLERANCE: f64 = 1e-6;
+
+#[test]
+fn matches_cpp_testapp() {
+    let p = parameters();
+    let features = features();
+    let mut train = Dataset::from_mat(&features, None, &p).unwrap();
+    train.set_field(Field::LABEL, &LABELS).unwrap();
+    let mut booster = Booster::new(Arc::new(train), &p).unwrap();
+
+    for i in 0..NUM_ITERATIONS {
+        assert!(
+            !booster.update_one_iter().unwrap(),
+            "training finished early at iteration {i}"
+        );
+    }
+    assert_eq!(booster.get_current_iteration().unwrap(), NUM_ITERATIONS);
+
+    let predictions = booster
+        .predict_for_mat(&features, PredictType::Normal, 0, None, &Parameters::new())
+        .unwrap();
+    assert_eq!(predictions.values().len(), GOLDEN_PREDICTIONS.len());
+    for (i, (&actual, expected)) in predictions
+        .values()
+        .iter()
+        .zip(GOLDEN_PREDICTIONS)
+        .enumerate()
+    {
+        assert!(
+            (actual - expected).abs() <= TOLERANCE,
+            "prediction {i} drifted: got {actual}, expected {expected}"
+        );
+    }
+
+    let importance = booster
+        .feature_importance(None, FeatureImportanceType::Split)
+        .unwrap();
+    assert_eq!(
+        importance, GOLDEN_SPLIT_IMPORTANCE,
+        "split importance drifted"
+    );
+}
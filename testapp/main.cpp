@@ -146,9 +146,25 @@ int main() {
 
     std::cout << "Training completed successfully!" << std::endl;
 
-    // Make predictions on the training data
+    // Make predictions on the training data. Multiclass models return
+    // num_class values per row, so size the buffer from the booster.
     int64_t num_predict = 0;
-    std::vector<double> predictions(num_data);
+    result = LGBM_BoosterCalcNumPredict(
+        booster,
+        num_data,
+        C_API_PREDICT_NORMAL,
+        0,  // start_iteration
+        -1, // num_iteration (use all)
+        &num_predict
+    );
+    if (result != 0) {
+        std::cerr << "Failed to get the prediction size. Error code: " << result << std::endl;
+        LGBM_BoosterFree(booster);
+        LGBM_DatasetFree(train_dataset);
+        return 1;
+    }
+    std::vector<double> predictions(num_predict);
+    int64_t num_per_row = num_predict / num_data;
 
     result = LGBM_BoosterPredictForMat(
         booster,
@@ -170,7 +186,11 @@ int main() {
         for (int i = 0; i < num_data; ++i) {
             std::cout << "  Sample " << i + 1 << ": "
                      << "Actual = " << train_labels[i]
-                     << ", Predicted = " << predictions[i] << std::endl;
+                     << ", Predicted =";
+            for (int64_t k = 0; k < num_per_row; ++k) {
+                std::cout << " " << predictions[i * num_per_row + k];
+            }
+            std::cout << std::endl;
         }
     } else {
         std::cerr << "Prediction failed. Error code: " << result << std::endl;